@@ -1,2 +1,3 @@
 pub mod render_layers;
+pub mod shake;
 pub mod target;
@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use rand::{Rng, thread_rng};
+
+use crate::game::{scene::internal::scene::MainCamera, state::state::AppState};
+
+pub struct ShakePlugin;
+impl Plugin for ShakePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenShake>()
+            .add_systems(OnExit(AppState::Level), stop_shake)
+            .add_systems(
+                FixedUpdate,
+                update_shake.run_if(in_state(AppState::Level)),
+            );
+    }
+}
+
+//moves the battle camera around the origin, fading out over `time`
+#[derive(Resource, Default)]
+pub struct ScreenShake {
+    pub strength: f32,
+    pub time: f32,
+    pub timer: f32,
+}
+
+impl ScreenShake {
+    //a weaker shake does not cut a stronger one short
+    pub fn shake(&mut self, strength: f32, time: f32) {
+        if self.timer > 0. && self.strength * self.timer / self.time > strength {
+            return;
+        }
+        self.strength = strength;
+        self.time = time;
+        self.timer = time;
+    }
+}
+
+fn update_shake(
+    mut shake: ResMut<ScreenShake>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+    time: Res<Time<Fixed>>,
+) {
+    let Ok(mut transform) = camera_query.single_mut() else {
+        return;
+    };
+    if shake.timer <= 0. {
+        transform.translation.x = 0.;
+        transform.translation.y = 0.;
+        return;
+    }
+    shake.timer -= time.delta_secs();
+    let strength = shake.strength * (shake.timer / shake.time).max(0.);
+    let mut rand = thread_rng();
+    transform.translation.x = rand.gen_range(-strength..=strength).round();
+    transform.translation.y = rand.gen_range(-strength..=strength).round();
+}
+
+fn stop_shake(mut shake: ResMut<ScreenShake>) {
+    shake.timer = 0.;
+}
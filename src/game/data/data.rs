@@ -1,8 +1,9 @@
-use bevy::prelude::*;
+use bevy::{asset::io::file::FileAssetReader, prelude::*};
 use serde::Deserialize;
 
 use crate::game::{
     animation::animation::Animation,
+    physics::collider::HitboxShape,
     toml::toml::{load_contents, read_toml},
 };
 
@@ -10,6 +11,7 @@ use crate::game::{
 pub struct Data {
     pub assets: AssetData,
     pub game: GameData,
+    pub attacks: AttackData,
 }
 
 #[derive(Deserialize, Clone, Default)]
@@ -19,11 +21,228 @@ pub struct GameData {
     pub battle: BattleData,
     pub fight_bar: FightBarData,
     pub board_layouts: BoardLayouts,
-    pub opponent_data: OpponentData,
+    pub enemy_death: EnemyDeathData,
+    #[serde(default)]
+    pub game_over: GameOverData,
+    #[serde(default)]
+    pub save: SaveData,
+    pub encounters: Vec<EncounterData>,
+    pub items: Vec<ItemData>,
+    pub overworld: OverworldData,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct GameOverData {
+    //seconds everything stays still before the soul breaks
+    pub freeze_time: f32,
+    //seconds the broken soul is shown before it shatters
+    pub break_time: f32,
+    //seconds the shards fly before GAME OVER fades in
+    pub shatter_time: f32,
+    //seconds the screen takes to fade in and out
+    pub fade_time: f32,
+
+    pub broken_sprite: String,
+    pub shard_sprite: String,
+    pub shard_count: i32,
+    //shards fly up to this fast in a random direction, then fall
+    pub shard_speed: f32,
+    pub shard_gravity: f32,
+
+    //seconds between each letter of the message
+    pub type_interval: f32,
+    //one is picked at random after every death
+    pub messages: Vec<String>,
+}
+impl Default for GameOverData {
+    fn default() -> Self {
+        GameOverData {
+            freeze_time: 0.7,
+            break_time: 1.2,
+            shatter_time: 1.5,
+            fade_time: 1.0,
+            broken_sprite: "sprites/soul_broken.png".to_string(),
+            shard_sprite: "sprites/soul_shard.png".to_string(),
+            shard_count: 6,
+            shard_speed: 5.0,
+            shard_gravity: 0.3,
+            type_interval: 0.06,
+            messages: vec!["Stay determined...".to_string()],
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct SaveData {
+    //folder inside the user's data directory
+    pub directory: String,
+    pub file: String,
+    //drawn on top of save objects in the overworld
+    pub sprite: String,
+    //dialogue set shown at save points that don't name their own
+    pub dialogue: String,
+    //continue from the save file when the game starts
+    pub load_on_start: bool,
+}
+impl Default for SaveData {
+    fn default() -> Self {
+        SaveData {
+            directory: "bevy_undertale".to_string(),
+            file: "save.toml".to_string(),
+            sprite: "sprites/save_point.png".to_string(),
+            dialogue: "save_point".to_string(),
+            load_on_start: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct OverworldData {
+    //map the game starts on, leave empty to start in the battle
+    pub map: String,
+    //spawn point object the walker starts at
+    pub spawn: String,
+    //size of a map pixel on screen
+    pub scale: f32,
+
+    pub speed: f32,
+    pub sprite: String,
+    pub atlas: String,
+    pub animations: String,
+    pub half_size_x: f32,
+    pub half_size_y: f32,
+    //how far in front of the walker objects can be interacted with
+    pub reach: f32,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct ItemData {
+    pub name: String,
+    pub heal: i32,
+    //lines shown in the text box when the item is used
+    pub text: Vec<String>,
+    pub consumable: bool,
+    //equippable items that aren't consumable are put on instead of used up
+    pub equippable: bool,
+
+    #[serde(default)]
+    pub slot: EquipSlot,
+    //added to the player's stats while the item is equipped
+    #[serde(default)]
+    pub at: i32,
+    #[serde(default)]
+    pub df: i32,
+    //how the FIGHT minigame plays with the item as the weapon
+    #[serde(default)]
+    pub fight: WeaponFightData,
+}
+
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EquipSlot {
+    #[default]
+    Weapon,
+    Armor,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct WeaponFightData {
+    //which game is played on the fight bar
+    pub minigame: FightMinigameKind,
+    //timing bars used by the games with more than one
+    pub bars: i32,
+    //distance between the bars when they start
+    pub bar_spacing: f32,
+    //pixels each frame, 0 uses the player's attack_speed
+    pub attack_speed: f32,
+    //distance from the center of the fight bar that counts as a critical hit
+    pub crit_width: f32,
+    //animation played by the slash over the enemy
+    pub slash: String,
+}
+impl Default for WeaponFightData {
+    fn default() -> Self {
+        WeaponFightData {
+            minigame: FightMinigameKind::Single,
+            bars: 1,
+            bar_spacing: 80.,
+            attack_speed: 0.,
+            crit_width: 12.,
+            slash: "slash".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FightMinigameKind {
+    //one bar from left to right
+    #[default]
+    Single,
+    //several bars one after another, stopped in order
+    Staggered,
+    //one bar from right to left
+    Reversed,
+    //bars from both sides, each press stops the one closest to the center
+    Rhythm,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct EncounterData {
+    pub name: String,
+    //flavor text shown in the text box, indexed by turn
+    pub dialogues: Vec<String>,
+    //horizontal distance between the enemies
+    pub spacing: f32,
+    //chance between 0 and 1 that fleeing succeeds
+    pub flee_chance: f32,
+    pub enemies: Vec<OpponentData>,
+    //rules that pick each turn's attack and text, the first matching rule is used
+    #[serde(default)]
+    pub script: Vec<TurnData>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TurnData {
+    //enemy the health conditions check and that attacks or speaks, empty uses the attacker
+    pub enemy: String,
+
+    pub min_turn: Option<i32>,
+    pub max_turn: Option<i32>,
+    //fractions of the enemy's max health
+    pub hp_below: Option<f32>,
+    pub hp_above: Option<f32>,
+    //flags that have to be set or unset by ACTs
+    pub flags: Vec<String>,
+    pub not_flags: Vec<String>,
+    pub items_used: Option<i32>,
+    pub item: String,
+
+    //weighted pools, empty pools fall through to the next rule
+    pub attacks: Vec<WeightedEntry>,
+    pub text: Vec<WeightedEntry>,
+    pub speech: Vec<WeightedEntry>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct WeightedEntry {
+    pub name: String,
+    pub weight: f32,
 }
 
 #[derive(Deserialize, Clone, Default)]
 pub struct OpponentData {
+    pub name: String,
+    pub sprite: String,
+    //atlas and animation bank the sprite is animated with, empty draws the sprite whole
+    #[serde(default)]
+    pub atlas: String,
+    #[serde(default)]
+    pub animations: String,
     pub height: f32,
     pub width: f32,
     pub health: i32,
@@ -31,6 +250,114 @@ pub struct OpponentData {
     pub at: i32,
     pub df: i32,
 
+    //shown after the stats when the enemy is checked
+    pub description: String,
+    //ACT options listed after Check
+    pub acts: Vec<ActData>,
+    //attacks the enemy uses, in order
+    pub attacks: Vec<String>,
+
+    //mercy needed from ACTs before the enemy can be spared, 0 disables it
+    pub spare_mercy: i32,
+    //turns after which the enemy can be spared, 0 disables it
+    pub spare_turns: i32,
+
+    //speech bubble shown when the enemy talks before attacking
+    #[serde(default)]
+    pub bubble: BubbleData,
+
+    //rewards, killing the enemy gives both and sparing it only the gold
+    #[serde(default)]
+    pub exp: i32,
+    #[serde(default)]
+    pub gold: i32,
+
+    //how the enemy reacts to being attacked
+    #[serde(default)]
+    pub hit: OpponentHitData,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct OpponentHitData {
+    //pixels the enemy moves to each side when the shake starts
+    pub shake: f32,
+    //shakes each second
+    pub shake_speed: f32,
+    //how fast the shake dies down, 1 shrinks it evenly and higher settles sooner
+    pub shake_decay: f32,
+    //image shown while shaking, empty keeps the sprite
+    pub hurt_sprite: String,
+    //animation played while shaking, only used with an animation bank
+    pub hurt_animation: String,
+    //sounds played when the damage lands and when the attack misses, empty plays nothing
+    pub sound: String,
+    pub miss_sound: String,
+    //shown over the enemy instead of the damage on a miss
+    pub miss_text: String,
+}
+impl Default for OpponentHitData {
+    fn default() -> Self {
+        OpponentHitData {
+            shake: 10.,
+            shake_speed: 10.,
+            shake_decay: 1.,
+            hurt_sprite: "".to_string(),
+            hurt_animation: "hurt".to_string(),
+            sound: "enemy_hit".to_string(),
+            miss_sound: "".to_string(),
+            miss_text: "MISS".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BubbleSide {
+    Left,
+    #[default]
+    Right,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct BubbleData {
+    pub width: f32,
+    pub height: f32,
+    //side of the enemy the bubble is drawn on
+    pub side: BubbleSide,
+    //sound played for each character
+    pub voice: String,
+}
+impl Default for BubbleData {
+    fn default() -> Self {
+        BubbleData {
+            width: 160.,
+            height: 80.,
+            side: BubbleSide::Right,
+            voice: "text".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ActData {
+    pub name: String,
+    //dialogue set played when the option is picked
+    pub dialogue: String,
+
+    //changes applied to the enemy's stats
+    pub at: i32,
+    pub df: i32,
+    pub mercy: i32,
+
+    //flags set for the rest of the battle, read by later turns
+    pub flags: Vec<String>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct EnemyDeathData {
     pub death_time: f32,
     pub dust_time : f32,
     pub dust_life : f32,
@@ -42,7 +369,351 @@ pub struct FightBarData {
 }
 #[derive(Deserialize, Clone, Default)]
 pub struct BattleData {
-    pub dialogues: Vec<String>,
+    //encounter that is started when the battle loads
+    pub encounter: String,
+    //bullets this far outside of the board are removed, 0 keeps them
+    #[serde(default)]
+    pub cull_margin: f32,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct AttackData {
+    pub attacks: Vec<AttackPattern>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct AttackPattern {
+    pub name: String,
+    //board layout the bullet board moves to when the attack starts
+    pub board: String,
+    pub duration: f32,
+    #[serde(default)]
+    pub spawners: Vec<SpawnerData>,
+    //mode the soul is put in when the attack starts
+    #[serde(default)]
+    pub soul: SoulMode,
+    //mode switches during the attack
+    #[serde(default)]
+    pub soul_changes: Vec<SoulChangeData>,
+    //platforms the blue soul can stand on
+    #[serde(default)]
+    pub platforms: Vec<PlatformData>,
+    #[serde(default)]
+    pub beams: Vec<BeamData>,
+    //layouts the board moves through one after the other once it reaches `board`
+    #[serde(default)]
+    pub board_keyframes: Vec<BoardKeyframeData>,
+    #[serde(default)]
+    pub emitters: Vec<EmitterData>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct BoardKeyframeData {
+    pub board: String,
+    //seconds the move takes, 0 moves at the board's own rates
+    pub duration: f32,
+    pub easing: Easing,
+    //seconds to hold the previous layout before moving
+    pub wait: f32,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    //pulls back before moving
+    BackIn,
+    //overshoots the target and comes back
+    BackOut,
+    BackInOut,
+    //wobbles around the start before leaving it
+    ElasticIn,
+    //wobbles around the target before settling
+    ElasticOut,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct BeamData {
+    //where the beam starts, relative to the board center
+    pub x: f32,
+    pub y: f32,
+    //degrees, 0 points right
+    pub angle: f32,
+    //points the beam at the soul when it appears instead of using the angle
+    pub aim: bool,
+    pub length: f32,
+    pub width: f32,
+
+    //seconds of the warning line before the beam fires
+    pub charge_time: f32,
+    //seconds the beam takes to reach its width, stays at it and takes to disappear
+    pub grow_time: f32,
+    pub fire_time: f32,
+    pub shrink_time: f32,
+
+    pub damage: i32,
+    pub kind: DamageKind,
+    //distance the screen shakes by when the beam fires
+    pub shake: f32,
+
+    //time before the first beam and between each beam after that
+    pub delay: f32,
+    pub interval: f32,
+    //amount of beams to fire, 0 keeps firing until the attack ends
+    pub waves: i32,
+}
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EmitterPattern {
+    //bullets evenly around the emitter
+    #[default]
+    Ring,
+    //bullets fanned over `spread` degrees around the angle
+    Spread,
+    //a ring that turns by `angle_step` after every shot
+    Spiral,
+    //a spread pointed at the soul
+    Aimed,
+    //bullets side by side `spacing` apart, all moving at the angle
+    Line,
+    //each bullet starts on a random edge of the board and moves inwards
+    RandomEdge,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EmitterAnchor {
+    //x and y are relative to the board center and the emitter moves with the board
+    #[default]
+    Board,
+    //x and y are relative to the soul and the emitter follows it
+    Player,
+    //x and y are relative to the board center when the attack starts
+    None,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct EmitterData {
+    pub pattern: EmitterPattern,
+    pub anchor: EmitterAnchor,
+    pub x: f32,
+    pub y: f32,
+    //movement of the emitter itself, only used without an anchor
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+
+    //time before the first shot and between each shot after that
+    pub delay: f32,
+    pub interval: f32,
+    //amount of shots before the emitter is removed, 0 keeps shooting
+    pub shots: i32,
+    //bullets per shot
+    pub count: i32,
+    //degrees, 0 points right
+    pub angle: f32,
+    //degrees covered by spread and aimed shots
+    pub spread: f32,
+    //degrees the angle turns after every shot, spirals use it but any pattern can
+    pub angle_step: f32,
+    pub spacing: f32,
+
+    pub sprite: String,
+    pub damage: i32,
+    pub kind: DamageKind,
+    pub half_hitbox_x: f32,
+    pub half_hitbox_y: f32,
+    pub shape: HitboxShape,
+    //turns the sprite to the direction the bullet moves in
+    pub face_velocity: bool,
+    pub speed: f32,
+    //speed added each frame, bullets don't go below 0 or above max_speed
+    pub acceleration: f32,
+    //0 leaves the speed unlimited
+    pub max_speed: f32,
+    //degrees the direction of each bullet turns every frame
+    pub angular_velocity: f32,
+    //seconds before the bullets are removed, 0 keeps them until the attack ends
+    pub lifetime: f32,
+}
+impl Default for EmitterData {
+    fn default() -> Self {
+        EmitterData {
+            pattern: EmitterPattern::Ring,
+            anchor: EmitterAnchor::Board,
+            x: 0.,
+            y: 0.,
+            velocity_x: 0.,
+            velocity_y: 0.,
+            delay: 0.,
+            interval: 1.,
+            shots: 0,
+            count: 1,
+            angle: 0.,
+            spread: 45.,
+            angle_step: 0.,
+            spacing: 16.,
+            sprite: "sprites/potato.png".to_string(),
+            damage: 4,
+            kind: DamageKind::Normal,
+            half_hitbox_x: 3.,
+            half_hitbox_y: 3.,
+            shape: HitboxShape::Box,
+            face_velocity: false,
+            speed: 2.,
+            acceleration: 0.,
+            max_speed: 0.,
+            angular_velocity: 0.,
+            lifetime: 0.,
+        }
+    }
+}
+
+impl Default for BeamData {
+    fn default() -> Self {
+        BeamData {
+            x: 0.,
+            y: 0.,
+            angle: 0.,
+            aim: false,
+            length: 400.,
+            width: 24.,
+            charge_time: 0.6,
+            grow_time: 0.1,
+            fire_time: 0.5,
+            shrink_time: 0.3,
+            damage: 5,
+            kind: DamageKind::Normal,
+            shake: 3.,
+            delay: 0.,
+            interval: 1.,
+            waves: 1,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DamageKind {
+    //hurts on touch
+    #[default]
+    Normal,
+    //only hurts while the soul is moving
+    Blue,
+    //only hurts while the soul is standing still
+    Orange,
+}
+
+#[derive(Component, Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SoulMode {
+    //moves freely
+    #[default]
+    Red,
+    //falls towards the bottom of the board and jumps with up
+    Blue,
+    //keeps moving in the last direction pressed
+    Orange,
+    //stays in place and blocks bullets with a shield
+    Green,
+    //moves along horizontal rails
+    Purple,
+    //moves freely and can't be hurt by normal bullets while standing still
+    Cyan,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct SoulChangeData {
+    //seconds into the attack
+    pub time: f32,
+    pub mode: SoulMode,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PlatformData {
+    //relative to the board center
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BoardEdge {
+    #[default]
+    Left,
+    Top,
+    Right,
+    Bottom,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SpawnerData {
+    pub sprite: String,
+    pub damage: i32,
+    pub kind: DamageKind,
+    pub half_hitbox_x: f32,
+    pub half_hitbox_y: f32,
+    pub shape: HitboxShape,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    //degrees, turns the bullet and its hitbox
+    pub rotation: f32,
+    //degrees added to the rotation each frame
+    pub spin: f32,
+    //adds the angle of the bullet's velocity to its rotation
+    pub face_velocity: bool,
+
+    //time before the first wave and between each wave after that
+    pub delay: f32,
+    pub interval: f32,
+    //amount of waves to spawn, 0 keeps spawning until the attack ends
+    pub waves: i32,
+    //spawns a wave every time the board settles instead of on the interval
+    pub on_settle: bool,
+
+    //edges of the board to spawn lines from, cycled through every wave
+    //bullets spawned on an edge move inwards at `speed`
+    pub edges: Vec<BoardEdge>,
+    pub speed: f32,
+
+    //used when no edges are given, relative to the board center
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+
+    //bullets per wave, 0 fills the whole edge
+    pub count: i32,
+    pub spacing: f32,
+    //indices in the line that are left empty
+    pub gaps: Vec<i32>,
+
+    //bullets start this far back and slide into their place in the line over slide_time
+    pub slide_x: f32,
+    pub slide_y: f32,
+    pub slide_time: f32,
+    //wave added on top of the movement, in pixels along each axis
+    pub sine_x: f32,
+    pub sine_y: f32,
+    //waves per second
+    pub sine_frequency: f32,
+    //pixels along x for the wave to repeat, the phase follows each bullet's x on the board
+    //so a line ripples, 0 keeps every bullet in step
+    pub sine_wavelength: f32,
 }
 #[derive(Deserialize, Clone, Default)]
 pub struct BoardLayouts {
@@ -53,12 +724,49 @@ pub struct BoardLayout {
     pub name: String,
     pub x: f32,
     pub y: f32,
+    //for polygon boards these only place the text and attacks, leave them
+    //at 0 to use the size of the polygon
     pub width: f32,
     pub height: f32,
+    //degrees, counter clockwise
+    #[serde(default)]
+    pub rotation: f32,
+    //degrees turned every frame, the board keeps turning until the next layout
+    #[serde(default)]
+    pub spin: f32,
+    //corners of a convex polygon around the position, replaces the rectangle
+    #[serde(default)]
+    pub points: Vec<[f32; 2]>,
+    //more boxes the soul can move between, they are drawn as one shape
+    #[serde(default)]
+    pub arenas: Vec<ArenaLayout>,
+    //seconds the board takes to move to this layout, 0 moves at the board's own rates
+    #[serde(default)]
+    pub duration: f32,
+    #[serde(default)]
+    pub easing: Easing,
+}
+#[derive(Deserialize, Clone, Default)]
+pub struct ArenaLayout {
+    pub x: f32,
+    pub y: f32,
+    #[serde(default)]
+    pub width: f32,
+    #[serde(default)]
+    pub height: f32,
+    #[serde(default)]
+    pub rotation: f32,
+    #[serde(default)]
+    pub spin: f32,
+    #[serde(default)]
+    pub points: Vec<[f32; 2]>,
 }
 
 #[derive(Deserialize, Clone, Default)]
 pub struct DialogueData {
+    //width of a character and height of a line, as a fraction of the font size
+    pub glyph_width: f32,
+    pub line_height: f32,
     pub dialogues: Vec<DialogueSet>,
 }
 
@@ -86,6 +794,68 @@ pub struct PlayerData {
 
     pub at: i32,
     pub df: i32,
+
+    //names of the items the player starts with
+    pub inventory: Vec<String>,
+    //items the player starts with equipped, they aren't in the inventory
+    #[serde(default)]
+    pub weapon: String,
+    #[serde(default)]
+    pub armor: String,
+
+    #[serde(default)]
+    pub soul: SoulData,
+
+    //LV 1 first, without it the player stays at LV 1 with the stats above
+    #[serde(default)]
+    pub levels: Vec<LevelData>,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct LevelData {
+    //total EXP needed to reach the level
+    pub exp: i32,
+    pub health: i32,
+    pub at: i32,
+    pub df: i32,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct SoulData {
+    //white copy of the soul that is tinted for every mode but red
+    pub sprite: String,
+
+    pub gravity: f32,
+    pub jump_speed: f32,
+    pub max_fall_speed: f32,
+    //upward speed that is kept when up is let go during a jump
+    pub release_speed: f32,
+
+    //distance between the soul and the shield
+    pub shield_distance: f32,
+    pub shield_length: f32,
+    //radians the shield turns each frame
+    pub shield_turn_speed: f32,
+
+    pub rail_count: i32,
+    pub rail_speed: f32,
+}
+impl Default for SoulData {
+    fn default() -> Self {
+        SoulData {
+            sprite: "sprites/soul_white.png".to_string(),
+            gravity: 0.5,
+            jump_speed: 7.0,
+            max_fall_speed: 8.0,
+            release_speed: 1.5,
+            shield_distance: 18.0,
+            shield_length: 28.0,
+            shield_turn_speed: 0.5,
+            rail_count: 3,
+            rail_speed: 6.0,
+        }
+    }
 }
 
 #[derive(Deserialize, Clone)]
@@ -109,6 +879,8 @@ pub struct AssetData {
     pub sounds: Vec<SoundData>,
     pub animations: Vec<AnimationGroup>,
     pub fonts: Vec<String>,
+    //tiled maps, embedded in the build by `overworld::map`
+    pub maps: Vec<String>,
 }
 
 #[derive(Deserialize, Clone, Default)]
@@ -132,6 +904,11 @@ pub fn setup_data(mut commands: Commands, mut data_res: ResMut<Data>) {
     let contents = include_str!("../../../assets/data/data.toml").to_string();
     let data: Option<GameData> = read_toml(contents);
 
+    //attacks are read when the game starts so they can be changed without a rebuild
+    let attack_path = FileAssetReader::get_base_path().join(ATTACKS_PATH);
+    let attack_contents = load_contents(attack_path.to_string_lossy().to_string());
+    let attack_data = read_attacks(attack_contents);
+
     log::info!("try loading data");
     if asset_data.is_some() {
         log::info!("got asset data");
@@ -143,4 +920,32 @@ pub fn setup_data(mut commands: Commands, mut data_res: ResMut<Data>) {
         let data_unwrapped = data.unwrap();
         data_res.game = data_unwrapped;
     }
+    if let Some(attacks) = attack_data {
+        log::info!("got attack data");
+        data_res.attacks = attacks;
+    }
+}
+
+const ATTACKS_PATH: &str = "assets/data/attacks.toml";
+
+//reads every attack on its own so a mistake in one only drops that attack
+fn read_attacks(contents: String) -> Option<AttackData> {
+    let table: toml::Table = read_toml(contents)?;
+    let Some(list) = table.get("attacks").and_then(|a| a.as_array()) else {
+        log::error!("{} has no [[attacks]]", ATTACKS_PATH);
+        return None;
+    };
+    let mut attacks = Vec::new();
+    for (i, entry) in list.iter().enumerate() {
+        let name = entry
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or("unnamed")
+            .to_string();
+        match entry.clone().try_into::<AttackPattern>() {
+            Ok(attack) => attacks.push(attack),
+            Err(e) => log::error!("could not read attack {} ({}) : {}", i, name, e),
+        }
+    }
+    Some(AttackData { attacks })
 }
@@ -1,21 +1,24 @@
 use crate::game::{
-    animation::animation::AtlasAnimationPlugin, camera::render_layers::RenderLayersPlugin,
-    data::data::DataPlugin, loading::loading::AssetManagerPlugin, physics::physics::PhysicsPlugin,
-    scene::internal::scene::ScenePlugin, sound::sound::SoundPlugin,
+    animation::animation::AtlasAnimationPlugin,
+    camera::{render_layers::RenderLayersPlugin, shake::ShakePlugin},
+    data::data::DataPlugin, loading::loading::AssetManagerPlugin,
+    overworld::overworld::OverworldPlugin, physics::physics::PhysicsPlugin,
+    save::save::SavePlugin, scene::internal::scene::ScenePlugin, sound::sound::SoundPlugin,
 };
 use bevy::prelude::*;
 use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
 
-use super::player::player::PlayerPlugin;
+use super::player::{inventory::InventoryPlugin, player::PlayerPlugin};
 
 pub struct GamePlugin;
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(ClearColor(Color::BLACK))
             .add_plugins((DataPlugin, AssetManagerPlugin))
-            .add_plugins(PlayerPlugin)
-            .add_plugins(RenderLayersPlugin)
+            .add_plugins((PlayerPlugin, InventoryPlugin, SavePlugin))
+            .add_plugins((RenderLayersPlugin, ShakePlugin))
             .add_plugins(ScenePlugin)
+            .add_plugins(OverworldPlugin)
             .add_plugins(AtlasAnimationPlugin)
             .add_plugins(PhysicsPlugin)
             .add_plugins(SoundPlugin)
@@ -5,8 +5,11 @@ use bevy::prelude::*;
 
 use crate::game::{
     animation::animation::Animation,
-    data::data::{BoardLayout, Data, DialogueSet, setup_data},
-    scene::internal::progress::Progress,
+    data::data::{
+        AttackPattern, BoardLayout, Data, DialogueSet, EncounterData, ItemData, setup_data,
+    },
+    overworld::map::load_maps,
+    scene::internal::{attack_pattern::validate_pattern, progress::Progress},
     state::state::AppState,
 };
 
@@ -34,6 +37,10 @@ pub struct AssetManager {
     pub animations: HashMap<String, HashMap<String, Animation>>,
     pub dialogue_storage: HashMap<String, DialogueSet>,
     pub board_layouts: HashMap<String, BoardLayout>,
+    pub attack_patterns: HashMap<String, AttackPattern>,
+    pub encounters: HashMap<String, EncounterData>,
+    pub items: HashMap<String, ItemData>,
+    pub maps: HashMap<String, tiled::Map>,
 }
 impl AssetManager {
     pub fn check_ready(&mut self, asset_server: &Res<AssetServer>) -> bool {
@@ -62,7 +69,6 @@ fn load_assets(
     mut progress: ResMut<Progress>,
 ) {
     progress.turns = data.game.player.start_turn;
-    progress.health = data.game.opponent_data.health;
 
     let images = &data.assets.images;
     let atlases = &data.assets.atlases;
@@ -71,6 +77,9 @@ fn load_assets(
     let dialogue = &data.game.dialogue;
     let fonts = &data.assets.fonts;
     let boards = &data.game.board_layouts.layouts;
+    let patterns = &data.attacks.attacks;
+    let encounters = &data.game.encounters;
+    let items = &data.game.items;
     for i in 0..fonts.len() {
         let path = fonts[i].clone();
         let handle = asset_manager.load_asset(path.clone(), &asset_server);
@@ -152,14 +161,39 @@ fn load_assets(
             .board_layouts
             .insert(board.name.clone(), board);
     }
+
+    for pattern in patterns.iter().cloned() {
+        validate_pattern(&pattern, &asset_manager);
+        log::info!("loaded {} attack pattern", pattern.name);
+        asset_manager
+            .attack_patterns
+            .insert(pattern.name.clone(), pattern);
+    }
+
+    for encounter in encounters.iter().cloned() {
+        asset_manager
+            .encounters
+            .insert(encounter.name.clone(), encounter);
+    }
+
+    for item in items.iter().cloned() {
+        asset_manager.items.insert(item.name.clone(), item);
+    }
+
+    asset_manager.maps = load_maps(&data.assets.maps);
 }
 
 fn check_assets(
     mut state: ResMut<NextState<AppState>>,
     mut asset_manager: ResMut<AssetManager>,
     asset_server: Res<AssetServer>,
+    data: Res<Data>,
 ) {
     if asset_manager.check_ready(&asset_server) {
-        state.set(AppState::Level);
+        if !data.game.overworld.map.is_empty() {
+            state.set(AppState::Overworld);
+        } else {
+            state.set(AppState::Level);
+        }
     }
 }
@@ -3,8 +3,10 @@ pub mod camera;
 pub mod data;
 pub mod game;
 pub mod loading;
+pub mod overworld;
 pub mod physics;
 pub mod player;
+pub mod save;
 pub mod scene;
 pub mod state;
 pub mod toml;
@@ -0,0 +1,234 @@
+use bevy::prelude::*;
+
+use crate::game::{
+    data::data::Data,
+    loading::loading::AssetManager,
+    overworld::{map::CurrentMap, overworld::OverworldItem, walker::Walker},
+    physics::physics_object::PhysicsComponent,
+    scene::internal::{
+        bullet_board::BulletBoard,
+        encounter::Encounter,
+        progress::Progress,
+        scene::{MainCamera, Resolution},
+        selection::MenuSelect,
+    },
+    sound::sound::SoundPlayer,
+    state::state::AppState,
+};
+
+pub struct BattleTransitionPlugin;
+impl Plugin for BattleTransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BattleTransition>()
+            .add_systems(OnExit(AppState::Overworld), clear_battle_transition)
+            .add_systems(
+                FixedUpdate,
+                update_battle_transition.run_if(in_state(AppState::Overworld)),
+            );
+    }
+}
+
+const ALERT_TIME: f32 = 0.6;
+//the soul blinks a few times over the black screen before flying off
+const FLASH_TIME: f32 = 0.45;
+const FLASH_INTERVAL: f32 = 0.075;
+const FLY_TIME: f32 = 0.5;
+//space between the top of the walker's sprite and the alert
+const ALERT_MARGIN: f32 = 12.0;
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum TransitionPhase {
+    #[default]
+    Idle,
+    //a "!" pops up over the walker
+    Alert,
+    //the screen goes black and the soul blinks where the walker stood
+    Flash,
+    //the soul flies to the FIGHT button
+    Fly,
+}
+
+//plays between touching an encounter in the overworld and the battle starting
+#[derive(Resource, Default)]
+pub struct BattleTransition {
+    pub phase: TransitionPhase,
+    pub encounter: String,
+    pub timer: f32,
+    //soul position relative to the camera when the flash starts
+    start: Vec2,
+    alert: Option<Entity>,
+    overlay: Option<Entity>,
+    soul: Option<Entity>,
+}
+
+impl BattleTransition {
+    pub fn active(&self) -> bool {
+        self.phase != TransitionPhase::Idle
+    }
+    pub fn start(&mut self, encounter: String) {
+        self.phase = TransitionPhase::Alert;
+        self.encounter = encounter;
+        self.timer = 0.;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_battle_transition(
+    mut commands: Commands,
+    mut transition: ResMut<BattleTransition>,
+    mut current_map: ResMut<CurrentMap>,
+    mut encounter: ResMut<Encounter>,
+    mut progress: ResMut<Progress>,
+    mut state: ResMut<NextState<AppState>>,
+    mut sounds: ResMut<SoundPlayer>,
+    mut soul_query: Query<(&mut Transform, &mut Visibility), Without<MainCamera>>,
+    walker_query: Query<(Entity, &Walker, &PhysicsComponent)>,
+    camera_query: Query<(Entity, &Transform), With<MainCamera>>,
+    menu: Res<MenuSelect>,
+    bullet_board: Res<BulletBoard>,
+    resolution: Res<Resolution>,
+    time: Res<Time<Fixed>>,
+    asset_manager: Res<AssetManager>,
+    data: Res<Data>,
+) {
+    if !transition.active() {
+        return;
+    }
+    let Ok((walker_entity, walker, physics)) = walker_query.single() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera_query.single() else {
+        return;
+    };
+    transition.timer += time.delta_secs();
+
+    match transition.phase {
+        TransitionPhase::Alert => {
+            if transition.alert.is_none() {
+                current_map.return_position = Some((physics.position, walker.facing));
+                let overworld = &data.game.overworld;
+                let size_y = data
+                    .assets
+                    .atlases
+                    .iter()
+                    .find(|a| a.name == overworld.atlas)
+                    .map_or(0., |a| a.size_y);
+                let height = size_y * overworld.scale - overworld.half_size_y;
+                let alert = commands
+                    .spawn((
+                        Text2d::new("!"),
+                        TextFont {
+                            font: asset_manager.fonts["fonts/DTM-Mono.ttf"].clone(),
+                            font_size: 32.0,
+                            font_smoothing: bevy::text::FontSmoothing::None,
+                            ..Default::default()
+                        },
+                        TextColor(Color::WHITE),
+                        Transform::from_translation(Vec3::new(0., height + ALERT_MARGIN, 2.0)),
+                    ))
+                    .id();
+                commands.entity(walker_entity).add_child(alert);
+                transition.alert = Some(alert);
+                sounds.play_sound_once_local(asset_manager.sounds["alert"].clone());
+            }
+            if transition.timer < ALERT_TIME {
+                return;
+            }
+            if let Some(alert) = transition.alert.take() {
+                commands.entity(alert).despawn();
+            }
+
+            //the overlay and soul follow the camera like the dialogue box
+            let overlay = commands
+                .spawn((
+                    Sprite::from_color(Color::BLACK, resolution.game_res),
+                    Transform::from_translation(Vec3::new(0., 0., 20.0)),
+                    Name::new("battle transition"),
+                    OverworldItem,
+                ))
+                .id();
+            transition.start = physics.position - camera_transform.translation.truncate();
+            let soul = commands
+                .spawn((
+                    Sprite {
+                        image: asset_manager.images[&data.game.player.sprite].clone(),
+                        ..Default::default()
+                    },
+                    Transform::from_translation(transition.start.extend(21.0)),
+                    OverworldItem,
+                ))
+                .id();
+            commands.entity(camera).add_children(&[overlay, soul]);
+            transition.overlay = Some(overlay);
+            transition.soul = Some(soul);
+            transition.phase = TransitionPhase::Flash;
+            transition.timer = 0.;
+            sounds.play_sound_once_local(asset_manager.sounds["battle_flash"].clone());
+        }
+        TransitionPhase::Flash => {
+            let Some(soul) = transition.soul else {
+                return;
+            };
+            if let Ok((_, mut visibility)) = soul_query.get_mut(soul) {
+                *visibility = Visibility::Inherited;
+                if (transition.timer / FLASH_INTERVAL) as i32 % 2 == 1 {
+                    *visibility = Visibility::Hidden;
+                }
+            }
+            if transition.timer >= FLASH_TIME {
+                if let Ok((_, mut visibility)) = soul_query.get_mut(soul) {
+                    *visibility = Visibility::Inherited;
+                }
+                transition.phase = TransitionPhase::Fly;
+                transition.timer = 0.;
+                sounds.play_sound_once_local(asset_manager.sounds["battle_fall"].clone());
+            }
+        }
+        TransitionPhase::Fly => {
+            let Some(soul) = transition.soul else {
+                return;
+            };
+            //the battle opens with FIGHT selected so the soul lands on its button
+            let target = menu.soul_position(0, &bullet_board, &data);
+            let t = (transition.timer / FLY_TIME).min(1.0);
+            if let Ok((mut transform, _)) = soul_query.get_mut(soul) {
+                let position = transition.start.lerp(target, t);
+                transform.translation.x = position.x.round();
+                transform.translation.y = position.y.round();
+            }
+            if t < 1.0 {
+                return;
+            }
+            let Some(encounter_data) = asset_manager.encounters.get(&transition.encounter) else {
+                //the walker stays in the overworld instead of entering a missing battle
+                log::warn!("no encounter {}", transition.encounter);
+                for entity in [transition.overlay.take(), transition.soul.take()]
+                    .into_iter()
+                    .flatten()
+                {
+                    commands.entity(entity).despawn();
+                }
+                current_map.return_position = None;
+                transition.phase = TransitionPhase::Idle;
+                transition.timer = 0.;
+                return;
+            };
+            encounter.begin(
+                encounter_data.clone(),
+                &mut progress,
+                data.game.player.start_turn,
+            );
+            state.set(AppState::Level);
+        }
+        TransitionPhase::Idle => {}
+    }
+}
+
+//the overlay and soul are overworld items so they are despawned with the rest
+fn clear_battle_transition(mut transition: ResMut<BattleTransition>) {
+    transition.phase = TransitionPhase::Idle;
+    transition.timer = 0.;
+    transition.alert = None;
+    transition.overlay = None;
+    transition.soul = None;
+}
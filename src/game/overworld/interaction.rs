@@ -0,0 +1,383 @@
+use bevy::prelude::*;
+use rand::{Rng, thread_rng};
+
+use crate::game::{
+    data::data::{Data, DialogueSet},
+    loading::loading::AssetManager,
+    overworld::{
+        battle_transition::BattleTransition,
+        map::{CurrentMap, MapObject},
+        overworld::OverworldItem,
+        stat_screen::StatScreen,
+        walker::Walker,
+    },
+    physics::{physics_object::PhysicsComponent, rectangle::Rectangle},
+    player::player::PlayerStats,
+    save::save::{SaveManager, Story},
+    scene::internal::{
+        markup::GlyphText,
+        scene::{MainCamera, Resolution},
+    },
+    sound::sound::SoundPlayer,
+    state::state::AppState,
+};
+
+pub struct InteractionPlugin;
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OverworldDialogue>()
+            .add_systems(OnExit(AppState::Overworld), close_dialogue)
+            .add_systems(
+                Update,
+                (interact, update_overworld_dialogue.after(interact))
+                    .run_if(in_state(AppState::Overworld)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (touch_objects, count_steps.after(touch_objects), update_overworld_text)
+                    .run_if(in_state(AppState::Overworld)),
+            );
+    }
+}
+
+const DIALOGUE_BOX_SIZE: Vec2 = Vec2::new(580.0, 140.0);
+const DIALOGUE_BORDER: f32 = 6.0;
+const DIALOGUE_PADDING: Vec2 = Vec2::new(28.0, 18.0);
+//distance between the box and the edge of the screen
+const DIALOGUE_MARGIN: f32 = 16.0;
+const DIALOGUE_VELOCITY: f32 = 30.0;
+
+//text box shown over the map when reading signs or talking to people
+#[derive(Resource, Default)]
+pub struct OverworldDialogue {
+    pub dialogue: Option<DialogueSet>,
+    pub dialogue_index: i32,
+    pub timer: f32,
+    pub prev_length: i32,
+    pub entity: Option<Entity>,
+    pub text_entity: Option<Entity>,
+}
+
+impl OverworldDialogue {
+    pub fn active(&self) -> bool {
+        self.dialogue.is_some()
+    }
+    fn line(&self) -> String {
+        if let Some(dialogue) = &self.dialogue {
+            return "* ".to_string() + dialogue.dialogue[self.dialogue_index as usize].as_str();
+        }
+        "".to_string()
+    }
+}
+
+//objects that do something when the walker steps on them
+fn touch_objects(
+    mut current_map: ResMut<CurrentMap>,
+    mut transition: ResMut<BattleTransition>,
+    walker_query: Query<&PhysicsComponent, With<Walker>>,
+    dialogue: Res<OverworldDialogue>,
+    asset_manager: Res<AssetManager>,
+) {
+    if dialogue.active() || transition.active() || current_map.queued.is_some() {
+        return;
+    }
+    let Ok(physics) = walker_query.single() else {
+        return;
+    };
+    let hitbox = Walker::hitbox(physics);
+    let mut touched: Option<MapObject> = None;
+    for i in 0..current_map.objects.len() {
+        let object = &current_map.objects[i];
+        if object.rect.intersects(hitbox) && (object.kind == "warp" || object.kind == "battle") {
+            touched = Some(object.clone());
+            break;
+        }
+    }
+    let Some(object) = touched else {
+        return;
+    };
+
+    if object.kind == "warp" {
+        let (Some(map), Some(spawn)) =
+            (object.properties.get("map"), object.properties.get("spawn"))
+        else {
+            //a broken warp is left out until the map is entered again
+            log::error!("warp {} needs a map and a spawn", object.name);
+            current_map.objects.retain(|o| o.id != object.id);
+            return;
+        };
+        current_map.queue(map.clone(), spawn.clone());
+    } else if object.kind == "battle" {
+        //a battle object is removed once it has been triggered
+        current_map.trigger(&object);
+        let Some(name) = object.properties.get("encounter") else {
+            log::error!("battle {} has no encounter", object.name);
+            return;
+        };
+        if !asset_manager.encounters.contains_key(name) {
+            log::error!("no encounter {}", name);
+            return;
+        }
+        transition.start(name.clone());
+    }
+}
+
+//walking inside an encounter zone counts down to a random battle
+fn count_steps(
+    mut current_map: ResMut<CurrentMap>,
+    mut transition: ResMut<BattleTransition>,
+    walker_query: Query<(&Walker, &PhysicsComponent)>,
+    asset_manager: Res<AssetManager>,
+) {
+    if transition.active() || current_map.queued.is_some() {
+        return;
+    }
+    let Ok((walker, physics)) = walker_query.single() else {
+        return;
+    };
+    if !walker.moving {
+        return;
+    }
+    let hitbox = Walker::hitbox(physics);
+    let Some(zone) = current_map
+        .objects
+        .iter()
+        .find(|o| o.kind == "encounter_zone" && o.rect.intersects(hitbox))
+        .cloned()
+    else {
+        return;
+    };
+
+    let mut rand = thread_rng();
+    if current_map.steps_left <= 0 {
+        let min_steps = read_steps(&zone, "min_steps", 1).max(1);
+        let max_steps = read_steps(&zone, "max_steps", min_steps).max(min_steps);
+        current_map.steps_left = rand.gen_range(min_steps..=max_steps);
+    }
+    current_map.steps_left -= 1;
+    if current_map.steps_left > 0 {
+        return;
+    }
+
+    let names = zone.properties.get("encounters").cloned().unwrap_or_default();
+    let encounters: Vec<&str> = names
+        .split(',')
+        .map(|e| e.trim())
+        .filter(|e| asset_manager.encounters.contains_key(*e))
+        .collect();
+    if encounters.is_empty() {
+        log::error!("no valid encounters in zone {}", zone.name);
+        return;
+    }
+    let name = encounters[rand.gen_range(0..encounters.len())];
+    transition.start(name.to_string());
+}
+
+fn read_steps(zone: &MapObject, property: &str, default: i32) -> i32 {
+    match zone.properties.get(property) {
+        Some(value) => value.parse::<i32>().unwrap_or(default),
+        None => default,
+    }
+}
+
+//objects that do something when Z is pressed while facing them
+#[allow(clippy::too_many_arguments)]
+fn interact(
+    mut commands: Commands,
+    mut dialogue: ResMut<OverworldDialogue>,
+    mut player_stats: ResMut<PlayerStats>,
+    mut save_manager: ResMut<SaveManager>,
+    mut story: ResMut<Story>,
+    mut sounds: ResMut<SoundPlayer>,
+    keys: Res<ButtonInput<KeyCode>>,
+    walker_query: Query<(&Walker, &PhysicsComponent)>,
+    camera_query: Query<(Entity, &Transform), With<MainCamera>>,
+    current_map: Res<CurrentMap>,
+    transition: Res<BattleTransition>,
+    stat_screen: Res<StatScreen>,
+    resolution: Res<Resolution>,
+    asset_manager: Res<AssetManager>,
+    data: Res<Data>,
+) {
+    if dialogue.active()
+        || transition.active()
+        || stat_screen.active()
+        || !keys.just_pressed(KeyCode::KeyZ)
+    {
+        return;
+    }
+    let Ok((walker, physics)) = walker_query.single() else {
+        return;
+    };
+    let reach = walker.facing.direction() * data.game.overworld.reach;
+    let hitbox = Rectangle::new_v(physics.position + reach, physics.half_hitbox);
+    for i in 0..current_map.objects.len() {
+        let object = &current_map.objects[i];
+        let usable = object.kind == "dialogue" || object.kind == "save";
+        if !usable || !object.rect.intersects(hitbox) {
+            continue;
+        }
+        let Ok((camera, camera_transform)) = camera_query.single() else {
+            return;
+        };
+        //the box moves to the top when the walker is on the bottom half of the screen
+        let mut side = -1.0;
+        if physics.position.y < camera_transform.translation.y {
+            side = 1.0;
+        }
+        let mut name = object.properties.get("dialogue").cloned().unwrap_or_default();
+        if object.kind == "save" {
+            //save points heal before saving so the file starts at full HP
+            player_stats.health = player_stats.max_health;
+            save_manager.save();
+            sounds.play_sound_once_local(asset_manager.sounds["save"].clone());
+            if name.is_empty() {
+                name = data.game.save.dialogue.clone();
+            }
+        }
+        if let Some(flag) = object.properties.get("flag") {
+            story.set_flag(flag, "true");
+        }
+        let Some(dialogue_set) = asset_manager.dialogue_storage.get(&name) else {
+            log::error!("no dialogue {}", name);
+            return;
+        };
+        dialogue.dialogue = Some(dialogue_set.clone());
+        dialogue.dialogue_index = 0;
+        dialogue.timer = 0.;
+        dialogue.prev_length = 0;
+        spawn_dialogue_box(
+            &mut commands,
+            &mut dialogue,
+            camera,
+            side * (resolution.game_res.y / 2.0 - DIALOGUE_MARGIN - DIALOGUE_BOX_SIZE.y / 2.0),
+            &asset_manager,
+            &data,
+        );
+        return;
+    }
+}
+
+fn spawn_dialogue_box(
+    commands: &mut Commands,
+    dialogue: &mut OverworldDialogue,
+    camera: Entity,
+    y: f32,
+    asset_manager: &AssetManager,
+    data: &Data,
+) {
+    let text_font = TextFont {
+        font: asset_manager.fonts["fonts/DTM-Mono.ttf"].clone(),
+        font_size: 26.0,
+        font_smoothing: bevy::text::FontSmoothing::None,
+        ..Default::default()
+    };
+    let font_size = text_font.font_size;
+    let mut glyph_text = GlyphText::new(
+        text_font,
+        DIALOGUE_BOX_SIZE.x - DIALOGUE_PADDING.x * 2.0,
+        font_size * data.game.dialogue.glyph_width,
+        font_size * data.game.dialogue.line_height,
+    );
+    glyph_text.velocity = DIALOGUE_VELOCITY;
+
+    let mut text_entity = None;
+    let e = commands
+        .spawn((
+            Sprite::from_color(Color::WHITE, DIALOGUE_BOX_SIZE),
+            Transform::from_translation(Vec3::new(0., y, 5.0)),
+            Visibility::Visible,
+            Name::new("overworld dialogue"),
+            OverworldItem,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Sprite::from_color(
+                    Color::BLACK,
+                    DIALOGUE_BOX_SIZE - Vec2::splat(DIALOGUE_BORDER * 2.0),
+                ),
+                Transform::from_translation(Vec3::new(0., 0., 0.1)),
+            ));
+            let text = builder
+                .spawn((
+                    glyph_text,
+                    Visibility::Inherited,
+                    Transform::from_translation(Vec3::new(
+                        -DIALOGUE_BOX_SIZE.x / 2.0 + DIALOGUE_PADDING.x,
+                        DIALOGUE_BOX_SIZE.y / 2.0 - DIALOGUE_PADDING.y,
+                        0.2,
+                    )),
+                ))
+                .id();
+            text_entity = Some(text);
+        })
+        .id();
+    //the box is a child of the camera so it stays on screen
+    commands.entity(camera).add_child(e);
+    dialogue.entity = Some(e);
+    dialogue.text_entity = text_entity;
+}
+
+fn update_overworld_dialogue(
+    mut commands: Commands,
+    mut dialogue: ResMut<OverworldDialogue>,
+    text_query: Query<&GlyphText>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    if !dialogue.active() || dialogue.text_entity.is_none() {
+        return;
+    }
+    let Ok(glyph_text) = text_query.get(dialogue.text_entity.unwrap()) else {
+        return;
+    };
+    let typed = glyph_text.finished(&dialogue.line(), dialogue.timer);
+    if keys.just_pressed(KeyCode::KeyX) {
+        dialogue.timer = 1000.0;
+    }
+    if keys.just_pressed(KeyCode::KeyZ) && typed {
+        dialogue.timer = 0.;
+        dialogue.prev_length = 0;
+        dialogue.dialogue_index += 1;
+    }
+
+    let len = dialogue.dialogue.as_ref().unwrap().dialogue.len();
+    if dialogue.dialogue_index >= len as i32 {
+        dialogue.dialogue = None;
+        if let Some(e) = dialogue.entity.take() {
+            commands.entity(e).despawn();
+        }
+        dialogue.text_entity = None;
+    }
+}
+
+fn update_overworld_text(
+    mut text_query: Query<&mut GlyphText>,
+    mut dialogue: ResMut<OverworldDialogue>,
+    mut sounds: ResMut<SoundPlayer>,
+    time: Res<Time<Fixed>>,
+    asset_manager: Res<AssetManager>,
+) {
+    if !dialogue.active() || dialogue.text_entity.is_none() {
+        return;
+    }
+    let Ok(mut glyph_text) = text_query.get_mut(dialogue.text_entity.unwrap()) else {
+        return;
+    };
+    if glyph_text.set_text(&dialogue.line()) {
+        dialogue.prev_length = 0;
+    }
+    dialogue.timer += time.delta_secs();
+    let length = glyph_text.revealed_at(dialogue.timer) as i32;
+
+    if (length - dialogue.prev_length) > 1 {
+        dialogue.prev_length = length;
+        sounds.play_sound_once_local(asset_manager.sounds["text"].clone());
+    }
+    glyph_text.revealed = length as usize;
+}
+
+fn close_dialogue(mut dialogue: ResMut<OverworldDialogue>) {
+    dialogue.dialogue = None;
+    dialogue.entity = None;
+    dialogue.text_entity = None;
+}
@@ -0,0 +1,346 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::Path,
+};
+
+use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+use tiled::{LayerType, ObjectShape, PropertyValue, ResourceReader};
+
+use crate::game::{
+    data::data::Data,
+    loading::loading::AssetManager,
+    overworld::{overworld::OverworldItem, walker::Facing},
+    physics::rectangle::Rectangle,
+};
+
+pub struct MapPlugin;
+impl Plugin for MapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CurrentMap>();
+    }
+}
+
+//maps are built into the game like the data files so they also load on the web
+const MAP_FILES: [(&str, &[u8]); 3] = [
+    ("maps/tiles.tsx", include_bytes!("../../../assets/maps/tiles.tsx")),
+    ("maps/ruins.tmx", include_bytes!("../../../assets/maps/ruins.tmx")),
+    (
+        "maps/ruins_hall.tmx",
+        include_bytes!("../../../assets/maps/ruins_hall.tmx"),
+    ),
+];
+
+struct EmbeddedReader;
+impl ResourceReader for EmbeddedReader {
+    type Resource = &'static [u8];
+    type Error = io::Error;
+
+    fn read_from(&mut self, path: &Path) -> Result<Self::Resource, Self::Error> {
+        let path = path.to_string_lossy().replace('\\', "/");
+        for (name, contents) in MAP_FILES {
+            if name == path {
+                return Ok(contents);
+            }
+        }
+        Err(io::Error::new(io::ErrorKind::NotFound, path))
+    }
+}
+
+pub fn load_maps(paths: &[String]) -> HashMap<String, tiled::Map> {
+    let mut maps = HashMap::new();
+    let mut loader =
+        tiled::Loader::with_cache_and_reader(tiled::DefaultResourceCache::new(), EmbeddedReader);
+    for path in paths {
+        match loader.load_tmx_map(path) {
+            Ok(map) => {
+                log::info!("loaded map {}", path);
+                maps.insert(path.clone(), map);
+            }
+            Err(e) => {
+                log::error!("failed to load map {} : {}", path, e);
+            }
+        }
+    }
+    maps
+}
+
+//an object placed in the map's object layers
+#[derive(Clone)]
+pub struct MapObject {
+    pub id: u32,
+    pub name: String,
+    //the object's class in tiled, e.g. warp, battle, encounter_zone, dialogue, save or spawn
+    pub kind: String,
+    pub rect: Rectangle,
+    pub properties: HashMap<String, String>,
+}
+
+#[derive(Resource, Default)]
+pub struct CurrentMap {
+    pub path: String,
+    //size of the map in screen pixels
+    pub size: Vec2,
+    pub scale: f32,
+    pub colliders: Vec<Rectangle>,
+    pub objects: Vec<MapObject>,
+
+    //map and spawn point to move to at the end of the frame
+    pub queued: Option<(String, String)>,
+    //battle objects that already fired, they stay gone when the map is rebuilt
+    pub triggered: HashSet<String>,
+    //steps left before a random encounter, rolled again when it reaches 0
+    pub steps_left: i32,
+    //where the walker was standing when a battle started
+    pub return_position: Option<(Vec2, Facing)>,
+}
+
+impl CurrentMap {
+    pub fn queue(&mut self, map: String, spawn: String) {
+        self.queued = Some((map, spawn));
+    }
+    //tiled measures from the top left of the map, the map is centered on the origin
+    pub fn to_world(&self, map_size: Vec2, x: f32, y: f32) -> Vec2 {
+        Vec2::new(x - map_size.x / 2.0, map_size.y / 2.0 - y) * self.scale
+    }
+    pub fn find_spawn(&self, name: &String) -> Vec2 {
+        for i in 0..self.objects.len() {
+            let object = &self.objects[i];
+            if object.kind == "spawn" && object.name == *name {
+                return Vec2::new(object.rect.middle_x(), object.rect.middle_y());
+            }
+        }
+        log::warn!("no spawn point {} in {}", name, self.path);
+        Vec2::ZERO
+    }
+    pub fn collides(&self, rect: Rectangle) -> bool {
+        self.colliders.iter().any(|c| c.intersects(rect))
+    }
+    pub fn trigger_key(&self, object: &MapObject) -> String {
+        self.path.clone() + "#" + object.id.to_string().as_str()
+    }
+    //removes a battle object for the rest of the game
+    pub fn trigger(&mut self, object: &MapObject) {
+        let key = self.trigger_key(object);
+        self.triggered.insert(key);
+        self.objects.retain(|o| o.id != object.id);
+    }
+}
+
+#[derive(Component)]
+pub struct MapEntity;
+
+//spawns the tile layers and reads the colliders and objects of a map
+pub fn build_map(
+    commands: &mut Commands,
+    current_map: &mut CurrentMap,
+    asset_manager: &AssetManager,
+    data: &Data,
+    path: &String,
+) {
+    let Some(map) = asset_manager.maps.get(path) else {
+        log::error!("no map {}", path);
+        return;
+    };
+    let tile_size = Vec2::new(map.tile_width as f32, map.tile_height as f32);
+    let map_size = Vec2::new(map.width as f32, map.height as f32) * tile_size;
+
+    current_map.path = path.clone();
+    current_map.scale = data.game.overworld.scale;
+    current_map.size = map_size * current_map.scale;
+    current_map.colliders.clear();
+    current_map.objects.clear();
+
+    for (i, layer) in map.layers().enumerate() {
+        match layer.layer_type() {
+            LayerType::Tiles(tile_layer) => {
+                spawn_tile_layer(
+                    commands,
+                    current_map,
+                    asset_manager,
+                    map,
+                    &tile_layer,
+                    map_size,
+                    i as f32,
+                );
+            }
+            LayerType::Objects(object_layer) => {
+                for object in object_layer.objects() {
+                    let (width, height) = match object.shape {
+                        ObjectShape::Rect { width, height } => (width, height),
+                        ObjectShape::Ellipse { width, height } => (width, height),
+                        _ => (0., 0.),
+                    };
+                    let top_left = current_map.to_world(map_size, object.x, object.y);
+                    let rect = Rectangle::new(
+                        top_left.x,
+                        top_left.y,
+                        width * current_map.scale,
+                        height * current_map.scale,
+                    );
+                    if layer.name == "collision" {
+                        current_map.colliders.push(rect);
+                        continue;
+                    }
+                    let map_object = MapObject {
+                        id: object.id(),
+                        name: object.name.clone(),
+                        kind: object.user_type.clone(),
+                        rect,
+                        properties: read_properties(&object.properties),
+                    };
+                    if current_map.triggered.contains(&current_map.trigger_key(&map_object)) {
+                        continue;
+                    }
+                    //a save point without its sprite can still be used
+                    if map_object.kind == "save" {
+                        if let Some(image) = asset_manager.images.get(&data.game.save.sprite) {
+                            let position = Vec2::new(rect.middle_x(), rect.middle_y());
+                            commands.spawn((
+                                Sprite::from_image(image.clone()),
+                                Transform::from_translation(position.extend(0.5)).with_scale(
+                                    Vec3::new(current_map.scale, current_map.scale, 1.0),
+                                ),
+                                Name::new("save point"),
+                                MapEntity,
+                                OverworldItem,
+                            ));
+                        } else {
+                            log::error!("no save point sprite {}", data.game.save.sprite);
+                        }
+                    }
+                    current_map.objects.push(map_object);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn spawn_tile_layer(
+    commands: &mut Commands,
+    current_map: &mut CurrentMap,
+    asset_manager: &AssetManager,
+    map: &tiled::Map,
+    tile_layer: &tiled::TileLayer,
+    map_size: Vec2,
+    z: f32,
+) {
+    let size = TilemapSize {
+        x: map.width,
+        y: map.height,
+    };
+    let tile_size = TilemapTileSize {
+        x: map.tile_width as f32,
+        y: map.tile_height as f32,
+    };
+    let grid_size: TilemapGridSize = tile_size.into();
+    let map_type = TilemapType::Square;
+
+    //every tileset needs its own tilemap since a tilemap has one texture
+    for (t, tileset) in map.tilesets().iter().enumerate() {
+        let Some(image) = &tileset.image else {
+            continue;
+        };
+        let image_path = image.source.to_string_lossy().replace('\\', "/");
+        let Some(texture) = asset_manager.images.get(&image_path) else {
+            log::error!("map {} uses unknown tileset image {}", current_map.path, image_path);
+            continue;
+        };
+
+        let tilemap = commands.spawn_empty().id();
+        let mut storage = TileStorage::empty(size);
+        for x in 0..map.width {
+            for y in 0..map.height {
+                let Some(tile) = tile_layer.get_tile(x as i32, y as i32) else {
+                    continue;
+                };
+                if tile.tileset_index() != t {
+                    continue;
+                }
+
+                if let Some(tile_data) = tile.get_tile()
+                    && let Some(PropertyValue::BoolValue(true)) = tile_data.properties.get("solid")
+                {
+                    let top_left = current_map.to_world(
+                        map_size,
+                        x as f32 * tile_size.x,
+                        y as f32 * tile_size.y,
+                    );
+                    current_map.colliders.push(Rectangle::new(
+                        top_left.x,
+                        top_left.y,
+                        tile_size.x * current_map.scale,
+                        tile_size.y * current_map.scale,
+                    ));
+                }
+
+                //tiled counts rows from the top, the tilemap from the bottom
+                let position = TilePos {
+                    x,
+                    y: map.height - 1 - y,
+                };
+                let tile_entity = commands
+                    .spawn((
+                        TileBundle {
+                            position,
+                            tilemap_id: TilemapId(tilemap),
+                            texture_index: TileTextureIndex(tile.id()),
+                            flip: TileFlip {
+                                x: tile.flip_h,
+                                y: tile.flip_v,
+                                d: tile.flip_d,
+                            },
+                            ..Default::default()
+                        },
+                        MapEntity,
+                        OverworldItem,
+                    ))
+                    .id();
+                storage.set(&position, tile_entity);
+            }
+        }
+
+        let transform = Transform::from_xyz(0.0, 0.0, z - 10.0)
+            .with_scale(Vec3::new(current_map.scale, current_map.scale, 1.0));
+        commands.entity(tilemap).insert((
+            TilemapBundle {
+                grid_size,
+                map_type,
+                size,
+                storage,
+                texture: TilemapTexture::Single(texture.clone()),
+                tile_size,
+                transform,
+                anchor: TilemapAnchor::Center,
+                ..Default::default()
+            },
+            Name::new("tilemap"),
+            MapEntity,
+            OverworldItem,
+        ));
+    }
+}
+
+fn read_properties(properties: &tiled::Properties) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    for (name, value) in properties.iter() {
+        let value = match value {
+            PropertyValue::StringValue(s) => s.clone(),
+            PropertyValue::IntValue(i) => i.to_string(),
+            PropertyValue::FloatValue(f) => f.to_string(),
+            PropertyValue::BoolValue(b) => b.to_string(),
+            PropertyValue::FileValue(f) => f.clone(),
+            _ => continue,
+        };
+        values.insert(name.clone(), value);
+    }
+    values
+}
+
+pub fn despawn_map(commands: &mut Commands, map_query: &Query<Entity, With<MapEntity>>) {
+    for e in map_query.iter() {
+        commands.entity(e).despawn();
+    }
+}
@@ -0,0 +1,7 @@
+pub mod battle_transition;
+pub mod interaction;
+pub mod map;
+#[allow(clippy::module_inception)]
+pub mod overworld;
+pub mod stat_screen;
+pub mod walker;
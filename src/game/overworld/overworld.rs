@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use bevy_ecs_tilemap::TilemapPlugin;
+
+use crate::game::{
+    data::data::Data,
+    loading::loading::AssetManager,
+    overworld::{
+        battle_transition::BattleTransitionPlugin,
+        interaction::InteractionPlugin,
+        map::{CurrentMap, MapEntity, MapPlugin, build_map, despawn_map},
+        stat_screen::StatScreenPlugin,
+        walker::{Walker, WalkerPlugin},
+    },
+    physics::physics_object::PhysicsComponent,
+    scene::internal::scene::MainCamera,
+    state::state::AppState,
+};
+
+pub struct OverworldPlugin;
+impl Plugin for OverworldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            TilemapPlugin,
+            MapPlugin,
+            WalkerPlugin,
+            InteractionPlugin,
+            BattleTransitionPlugin,
+            StatScreenPlugin,
+        ))
+            .add_systems(OnEnter(AppState::Overworld), enter_overworld)
+            .add_systems(OnExit(AppState::Overworld), exit_overworld)
+            .add_systems(
+                FixedUpdate,
+                change_map.run_if(in_state(AppState::Overworld)),
+            );
+    }
+}
+
+//despawned when leaving the overworld
+#[derive(Component)]
+pub struct OverworldItem;
+
+fn enter_overworld(
+    mut commands: Commands,
+    mut current_map: ResMut<CurrentMap>,
+    asset_manager: Res<AssetManager>,
+    data: Res<Data>,
+) {
+    if current_map.path.is_empty() {
+        current_map.queue(
+            data.game.overworld.map.clone(),
+            data.game.overworld.spawn.clone(),
+        );
+    }
+    let path = current_map.path.clone();
+    if !path.is_empty() {
+        build_map(&mut commands, &mut current_map, &asset_manager, &data, &path);
+    }
+}
+
+fn exit_overworld(
+    mut commands: Commands,
+    overworld_query: Query<Entity, With<OverworldItem>>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+) {
+    for e in overworld_query.iter() {
+        commands.entity(e).despawn();
+    }
+    //the battle is laid out around the origin
+    if let Ok(mut transform) = camera_query.single_mut() {
+        transform.translation.x = 0.;
+        transform.translation.y = 0.;
+    }
+}
+
+fn change_map(
+    mut commands: Commands,
+    mut current_map: ResMut<CurrentMap>,
+    mut walker_query: Query<&mut PhysicsComponent, With<Walker>>,
+    map_query: Query<Entity, With<MapEntity>>,
+    asset_manager: Res<AssetManager>,
+    data: Res<Data>,
+) {
+    let Some((path, spawn)) = current_map.queued.take() else {
+        return;
+    };
+    if !asset_manager.maps.contains_key(&path) {
+        log::error!("no map {}", path);
+        return;
+    }
+    despawn_map(&mut commands, &map_query);
+    build_map(&mut commands, &mut current_map, &asset_manager, &data, &path);
+
+    if let Ok(mut physics) = walker_query.single_mut() {
+        physics.position = current_map.find_spawn(&spawn);
+    }
+}
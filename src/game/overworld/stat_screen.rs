@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+
+use crate::game::{
+    data::data::Data,
+    loading::loading::AssetManager,
+    overworld::{
+        battle_transition::BattleTransition, interaction::OverworldDialogue,
+        overworld::OverworldItem,
+    },
+    player::{inventory::Inventory, player::PlayerStats},
+    scene::internal::scene::MainCamera,
+    state::state::AppState,
+};
+
+pub struct StatScreenPlugin;
+impl Plugin for StatScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StatScreen>()
+            .add_systems(OnExit(AppState::Overworld), close_stat_screen)
+            .add_systems(
+                Update,
+                toggle_stat_screen.run_if(in_state(AppState::Overworld)),
+            );
+    }
+}
+
+const STAT_BOX_SIZE: Vec2 = Vec2::new(340.0, 420.0);
+const STAT_BORDER: f32 = 6.0;
+const STAT_PADDING: Vec2 = Vec2::new(28.0, 24.0);
+
+//panel with the player's stats and equipment, opened with C on the overworld
+#[derive(Resource, Default)]
+pub struct StatScreen {
+    pub entity: Option<Entity>,
+}
+
+impl StatScreen {
+    pub fn active(&self) -> bool {
+        self.entity.is_some()
+    }
+}
+
+fn stat_text(
+    player_stats: &PlayerStats,
+    inventory: &Inventory,
+    asset_manager: &AssetManager,
+    data: &Data,
+) -> String {
+    let items = &asset_manager.items;
+    let bonus_at = inventory.bonus_at(items);
+    let bonus_df = inventory.bonus_df(items);
+    //EXP still missing for the next level, nothing past the end of the table
+    let levels = &data.game.player.levels;
+    let mut next = 0;
+    if (player_stats.lv as usize) < levels.len() {
+        next = (levels[player_stats.lv as usize].exp - player_stats.exp).max(0);
+    }
+    let mut text = "\"".to_string() + &player_stats.name + "\"\n\n";
+    text += &format!("LV {}\n", player_stats.lv);
+    text += &format!(
+        "HP {} / {}\n\n",
+        player_stats.health, player_stats.max_health
+    );
+    text += &format!("AT {} ({})\n", player_stats.at, bonus_at);
+    text += &format!("DF {} ({})\n\n", player_stats.df, bonus_df);
+    text += &format!("EXP: {}\n", player_stats.exp);
+    text += &format!("NEXT: {}\n\n", next);
+    text += &format!("WEAPON: {}\n", inventory.weapon);
+    text += &format!("ARMOR: {}\n\n", inventory.armor);
+    text += &format!("GOLD: {}", player_stats.gold);
+    text
+}
+
+#[allow(clippy::too_many_arguments)]
+fn toggle_stat_screen(
+    mut commands: Commands,
+    mut stat_screen: ResMut<StatScreen>,
+    keys: Res<ButtonInput<KeyCode>>,
+    camera_query: Query<Entity, With<MainCamera>>,
+    dialogue: Res<OverworldDialogue>,
+    transition: Res<BattleTransition>,
+    player_stats: Res<PlayerStats>,
+    inventory: Res<Inventory>,
+    asset_manager: Res<AssetManager>,
+    data: Res<Data>,
+) {
+    if stat_screen.active() {
+        if (keys.just_pressed(KeyCode::KeyC) || keys.just_pressed(KeyCode::KeyX))
+            && let Some(e) = stat_screen.entity.take()
+        {
+            commands.entity(e).despawn();
+        }
+        return;
+    }
+    if dialogue.active() || transition.active() || !keys.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+
+    let text = stat_text(&player_stats, &inventory, &asset_manager, &data);
+    let e = commands
+        .spawn((
+            Sprite::from_color(Color::WHITE, STAT_BOX_SIZE),
+            Transform::from_translation(Vec3::new(0., 0., 5.0)),
+            Visibility::Visible,
+            Name::new("stat screen"),
+            OverworldItem,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                Sprite::from_color(Color::BLACK, STAT_BOX_SIZE - Vec2::splat(STAT_BORDER * 2.0)),
+                Transform::from_translation(Vec3::new(0., 0., 0.1)),
+            ));
+            builder.spawn((
+                Text2d::new(text),
+                TextLayout::new(JustifyText::Left, LineBreak::NoWrap),
+                TextFont {
+                    font: asset_manager.fonts["fonts/DTM-Mono.ttf"].clone(),
+                    font_size: 26.0,
+                    font_smoothing: bevy::text::FontSmoothing::None,
+                    ..Default::default()
+                },
+                TextColor(Color::WHITE),
+                bevy::sprite::Anchor::TopLeft,
+                Transform::from_translation(Vec3::new(
+                    -STAT_BOX_SIZE.x / 2.0 + STAT_PADDING.x,
+                    STAT_BOX_SIZE.y / 2.0 - STAT_PADDING.y,
+                    0.2,
+                )),
+            ));
+        })
+        .id();
+    //the box is a child of the camera so it stays on screen
+    commands.entity(camera).add_child(e);
+    stat_screen.entity = Some(e);
+}
+
+fn close_stat_screen(mut stat_screen: ResMut<StatScreen>) {
+    stat_screen.entity = None;
+}
@@ -0,0 +1,244 @@
+use bevy::{prelude::*, transform::TransformSystem};
+
+use crate::game::{
+    animation::animation::Animator,
+    data::data::Data,
+    loading::loading::AssetManager,
+    overworld::{
+        battle_transition::BattleTransition, interaction::OverworldDialogue, map::CurrentMap,
+        overworld::OverworldItem, stat_screen::StatScreen,
+    },
+    physics::{physics_object::PhysicsComponent, rectangle::Rectangle},
+    scene::internal::scene::{MainCamera, Resolution},
+    state::state::AppState,
+};
+
+pub struct WalkerPlugin;
+impl Plugin for WalkerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Overworld), spawn_walker)
+            .add_systems(
+                FixedUpdate,
+                (walker_movement, animate_walker.after(walker_movement))
+                    .run_if(in_state(AppState::Overworld)),
+            )
+            .add_systems(
+                PostUpdate,
+                follow_walker
+                    .before(TransformSystem::TransformPropagate)
+                    .run_if(in_state(AppState::Overworld)),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Facing {
+    #[default]
+    Down,
+    Up,
+    Left,
+    Right,
+}
+
+impl Facing {
+    pub fn direction(&self) -> Vec2 {
+        match self {
+            Facing::Down => Vec2::new(0., -1.),
+            Facing::Up => Vec2::new(0., 1.),
+            Facing::Left => Vec2::new(-1., 0.),
+            Facing::Right => Vec2::new(1., 0.),
+        }
+    }
+    pub fn name(&self) -> &str {
+        match self {
+            Facing::Down => "down",
+            Facing::Up => "up",
+            Facing::Left => "left",
+            Facing::Right => "right",
+        }
+    }
+}
+
+//the character walking around the overworld, its position is the center of its feet
+#[derive(Component, Default)]
+pub struct Walker {
+    pub facing: Facing,
+    pub moving: bool,
+}
+
+impl Walker {
+    pub fn hitbox(physics: &PhysicsComponent) -> Rectangle {
+        Rectangle::new_v(physics.position, physics.half_hitbox)
+    }
+}
+
+#[derive(Component)]
+pub struct WalkerSprite;
+
+fn spawn_walker(
+    mut commands: Commands,
+    asset_manager: Res<AssetManager>,
+    data: Res<Data>,
+    mut current_map: ResMut<CurrentMap>,
+) {
+    let overworld = &data.game.overworld;
+    let half_size = Vec2::new(overworld.half_size_x, overworld.half_size_y);
+
+    let mut position = Vec2::ZERO;
+    let mut facing = Facing::Down;
+    if let Some((return_position, return_facing)) = current_map.return_position.take() {
+        //coming back from a battle
+        position = return_position;
+        facing = return_facing;
+    } else if !current_map.path.is_empty() {
+        position = current_map.find_spawn(&overworld.spawn);
+    }
+    let walker = commands
+        .spawn((
+            Transform::from_translation(position.extend(1.0)),
+            Visibility::Visible,
+            PhysicsComponent::new_full(position, Vec2::ZERO, half_size, half_size),
+            Walker {
+                facing,
+                moving: false,
+            },
+            Name::new("walker"),
+            OverworldItem,
+        ))
+        .id();
+
+    //the walker still moves around without a sprite when its assets are missing
+    let (Some(atlas), Some(image), Some(layout), Some(animations)) = (
+        data.assets
+            .atlases
+            .iter()
+            .find(|a| a.name == overworld.atlas),
+        asset_manager.images.get(&overworld.sprite),
+        asset_manager.atlases.get(&overworld.atlas),
+        asset_manager.animations.get(&overworld.animations),
+    ) else {
+        log::error!(
+            "the walker uses unknown sprite {}, atlas {} or animations {}",
+            overworld.sprite,
+            overworld.atlas,
+            overworld.animations
+        );
+        return;
+    };
+    commands.entity(walker).with_children(|builder| {
+        //the sprite stands on the hitbox instead of being centered on it
+        builder.spawn((
+            Sprite::from_atlas_image(
+                image.clone(),
+                TextureAtlas {
+                    layout: layout.clone(),
+                    index: 0,
+                },
+            ),
+            Transform::from_translation(Vec3::new(
+                0.,
+                atlas.size_y * overworld.scale / 2.0 - half_size.y,
+                0.,
+            ))
+            .with_scale(Vec3::new(overworld.scale, overworld.scale, 1.0)),
+            Animator {
+                current_animation: "idle_".to_string() + facing.name(),
+                animation_bank: animations.clone(),
+                ..Default::default()
+            },
+            WalkerSprite,
+        ));
+    });
+}
+
+fn walker_movement(
+    input: Res<ButtonInput<KeyCode>>,
+    mut walker_query: Query<(&mut Walker, &mut PhysicsComponent)>,
+    current_map: Res<CurrentMap>,
+    dialogue: Res<OverworldDialogue>,
+    transition: Res<BattleTransition>,
+    stat_screen: Res<StatScreen>,
+    data: Res<Data>,
+) {
+    for (mut walker, mut physics) in walker_query.iter_mut() {
+        walker.moving = false;
+        if dialogue.active() || transition.active() || stat_screen.active() {
+            continue;
+        }
+        let mut horizontal = 0.;
+        let mut vertical = 0.;
+        if input.pressed(KeyCode::ArrowUp) {
+            vertical += 1.;
+        }
+        if input.pressed(KeyCode::ArrowDown) {
+            vertical -= 1.;
+        }
+        if input.pressed(KeyCode::ArrowRight) {
+            horizontal += 1.;
+        }
+        if input.pressed(KeyCode::ArrowLeft) {
+            horizontal -= 1.;
+        }
+
+        //vertical facing wins when walking diagonally
+        if horizontal < 0. {
+            walker.facing = Facing::Left;
+        } else if horizontal > 0. {
+            walker.facing = Facing::Right;
+        }
+        if vertical < 0. {
+            walker.facing = Facing::Down;
+        } else if vertical > 0. {
+            walker.facing = Facing::Up;
+        }
+
+        let speed = data.game.overworld.speed;
+        let start = physics.position;
+        //each axis is moved on its own so the walker slides along walls
+        physics.position.x += horizontal * speed;
+        if current_map.collides(Walker::hitbox(&physics)) {
+            physics.position.x = start.x;
+        }
+        physics.position.y += vertical * speed;
+        if current_map.collides(Walker::hitbox(&physics)) {
+            physics.position.y = start.y;
+        }
+        walker.moving = physics.position != start;
+    }
+}
+
+fn animate_walker(
+    walker_query: Query<&Walker>,
+    mut sprite_query: Query<&mut Animator, With<WalkerSprite>>,
+) {
+    let Ok(walker) = walker_query.single() else {
+        return;
+    };
+    if let Ok(mut animator) = sprite_query.single_mut() {
+        let mut state = "idle_";
+        if walker.moving {
+            state = "walk_";
+        }
+        animator.current_animation = state.to_string() + walker.facing.name();
+    }
+}
+
+fn follow_walker(
+    walker_query: Query<&PhysicsComponent, With<Walker>>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+    current_map: Res<CurrentMap>,
+    resolution: Res<Resolution>,
+) {
+    let Ok(physics) = walker_query.single() else {
+        return;
+    };
+    let Ok(mut transform) = camera_query.single_mut() else {
+        return;
+    };
+    //the camera stops at the edges of maps that are bigger than the screen
+    let mut position = physics.position;
+    let limit = ((current_map.size - resolution.game_res) / 2.0).max(Vec2::ZERO);
+    position = position.clamp(-limit, limit);
+    transform.translation.x = position.x.round();
+    transform.translation.y = position.y.round();
+}
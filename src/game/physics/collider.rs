@@ -0,0 +1,367 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+//shape of a hitbox, sized by the physics component's half_hitbox unless noted
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HitboxShape {
+    //box of half_hitbox turned by the rotation
+    #[default]
+    Box,
+    //circle with a radius of half_hitbox.x
+    Circle,
+    //segment of half_hitbox.x on each side of the center along the rotation,
+    //rounded by a radius of half_hitbox.y
+    Capsule,
+    //thin line of half_hitbox.x on each side of the center along the rotation
+    Segment,
+}
+
+//a hitbox placed in the world, used for the bullet and soul collision tests
+#[derive(Clone, Copy)]
+pub enum Collider {
+    Box {
+        center: Vec2,
+        half_size: Vec2,
+        rotation: f32,
+    },
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+    Capsule {
+        start: Vec2,
+        end: Vec2,
+        radius: f32,
+    },
+}
+
+impl Collider {
+    pub fn new(shape: HitboxShape, center: Vec2, half_hitbox: Vec2, rotation: f32) -> Collider {
+        let direction = Vec2::from_angle(rotation);
+        match shape {
+            HitboxShape::Box => Collider::Box {
+                center,
+                half_size: half_hitbox,
+                rotation,
+            },
+            HitboxShape::Circle => Collider::Circle {
+                center,
+                radius: half_hitbox.x,
+            },
+            HitboxShape::Capsule => Collider::Capsule {
+                start: center - direction * half_hitbox.x,
+                end: center + direction * half_hitbox.x,
+                radius: half_hitbox.y,
+            },
+            HitboxShape::Segment => Collider::Capsule {
+                start: center - direction * half_hitbox.x,
+                end: center + direction * half_hitbox.x,
+                radius: 0.,
+            },
+        }
+    }
+
+    pub fn intersects(&self, other: &Collider) -> bool {
+        match (*self, *other) {
+            (
+                Collider::Box {
+                    center,
+                    half_size,
+                    rotation,
+                },
+                Collider::Box {
+                    center: center_2,
+                    half_size: half_size_2,
+                    rotation: rotation_2,
+                },
+            ) => boxes_intersect(
+                center,
+                half_size,
+                rotation,
+                center_2,
+                half_size_2,
+                rotation_2,
+            ),
+            (
+                Collider::Circle { center, radius },
+                Collider::Circle {
+                    center: center_2,
+                    radius: radius_2,
+                },
+            ) => center.distance(center_2) <= radius + radius_2,
+            (
+                Collider::Capsule { start, end, radius },
+                Collider::Capsule {
+                    start: start_2,
+                    end: end_2,
+                    radius: radius_2,
+                },
+            ) => segment_distance(start, end, start_2, end_2) <= radius + radius_2,
+            (
+                Collider::Box {
+                    center,
+                    half_size,
+                    rotation,
+                },
+                Collider::Circle {
+                    center: point,
+                    radius,
+                },
+            ) => {
+                let local = to_local(point, center, rotation);
+                box_point_distance(half_size, local) <= radius
+            }
+            (
+                Collider::Box {
+                    center,
+                    half_size,
+                    rotation,
+                },
+                Collider::Capsule { start, end, radius },
+            ) => {
+                let local_start = to_local(start, center, rotation);
+                let local_end = to_local(end, center, rotation);
+                box_segment_distance(half_size, local_start, local_end) <= radius
+            }
+            (
+                Collider::Circle { center, radius },
+                Collider::Capsule {
+                    start,
+                    end,
+                    radius: radius_2,
+                },
+            ) => point_segment_distance(center, start, end) <= radius + radius_2,
+            //the remaining pairs are the ones above the other way around
+            _ => other.intersects(self),
+        }
+    }
+}
+
+//point relative to a box's center and rotation
+fn to_local(point: Vec2, center: Vec2, rotation: f32) -> Vec2 {
+    Vec2::from_angle(-rotation).rotate(point - center)
+}
+
+//separating axis test, the only axes two boxes can be separated on are their edge normals
+fn boxes_intersect(
+    center: Vec2,
+    half_size: Vec2,
+    rotation: f32,
+    center_2: Vec2,
+    half_size_2: Vec2,
+    rotation_2: f32,
+) -> bool {
+    let axes_1 = [
+        Vec2::from_angle(rotation),
+        Vec2::from_angle(rotation).perp(),
+    ];
+    let axes_2 = [
+        Vec2::from_angle(rotation_2),
+        Vec2::from_angle(rotation_2).perp(),
+    ];
+    let offset = center_2 - center;
+    let axes = [axes_1[0], axes_1[1], axes_2[0], axes_2[1]];
+    for axis in axes {
+        let extent_1 =
+            half_size.x * axes_1[0].dot(axis).abs() + half_size.y * axes_1[1].dot(axis).abs();
+        let extent_2 =
+            half_size_2.x * axes_2[0].dot(axis).abs() + half_size_2.y * axes_2[1].dot(axis).abs();
+        if offset.dot(axis).abs() > extent_1 + extent_2 {
+            return false;
+        }
+    }
+    true
+}
+
+//distance from a point to an axis aligned box around the origin, 0 when inside
+fn box_point_distance(half_size: Vec2, point: Vec2) -> f32 {
+    (point.abs() - half_size).max(Vec2::ZERO).length()
+}
+
+fn box_segment_distance(half_size: Vec2, start: Vec2, end: Vec2) -> f32 {
+    if segment_crosses_box(half_size, start, end) {
+        return 0.;
+    }
+    //when they don't touch the closest points are a segment end or a box corner
+    let mut distance = box_point_distance(half_size, start).min(box_point_distance(half_size, end));
+    let corners = [
+        Vec2::new(-half_size.x, -half_size.y),
+        Vec2::new(half_size.x, -half_size.y),
+        Vec2::new(half_size.x, half_size.y),
+        Vec2::new(-half_size.x, half_size.y),
+    ];
+    for corner in corners {
+        distance = distance.min(point_segment_distance(corner, start, end));
+    }
+    distance
+}
+
+//slab test of a segment against an axis aligned box around the origin
+fn segment_crosses_box(half_size: Vec2, start: Vec2, end: Vec2) -> bool {
+    let direction = end - start;
+    let mut t_min: f32 = 0.;
+    let mut t_max: f32 = 1.;
+    let starts = [start.x, start.y];
+    let directions = [direction.x, direction.y];
+    let extents = [half_size.x, half_size.y];
+    for i in 0..2 {
+        if directions[i].abs() < f32::EPSILON {
+            if starts[i].abs() > extents[i] {
+                return false;
+            }
+            continue;
+        }
+        let mut t_1 = (-extents[i] - starts[i]) / directions[i];
+        let mut t_2 = (extents[i] - starts[i]) / directions[i];
+        if t_1 > t_2 {
+            std::mem::swap(&mut t_1, &mut t_2);
+        }
+        t_min = t_min.max(t_1);
+        t_max = t_max.min(t_2);
+        if t_min > t_max {
+            return false;
+        }
+    }
+    true
+}
+
+pub fn point_segment_distance(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let direction = end - start;
+    let length_squared = direction.length_squared();
+    if length_squared < f32::EPSILON {
+        return point.distance(start);
+    }
+    let t = ((point - start).dot(direction) / length_squared).clamp(0., 1.);
+    point.distance(start + direction * t)
+}
+
+fn segment_distance(start: Vec2, end: Vec2, start_2: Vec2, end_2: Vec2) -> f32 {
+    if segments_cross(start, end, start_2, end_2) {
+        return 0.;
+    }
+    point_segment_distance(start, start_2, end_2)
+        .min(point_segment_distance(end, start_2, end_2))
+        .min(point_segment_distance(start_2, start, end))
+        .min(point_segment_distance(end_2, start, end))
+}
+
+fn segments_cross(start: Vec2, end: Vec2, start_2: Vec2, end_2: Vec2) -> bool {
+    let direction = end - start;
+    let direction_2 = end_2 - start_2;
+    let denominator = direction.perp_dot(direction_2);
+    if denominator.abs() < f32::EPSILON {
+        return false;
+    }
+    let offset = start_2 - start;
+    let t = offset.perp_dot(direction_2) / denominator;
+    let u = offset.perp_dot(direction) / denominator;
+    (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use super::*;
+
+    fn rect(center: Vec2, half_size: Vec2, rotation: f32) -> Collider {
+        Collider::new(HitboxShape::Box, center, half_size, rotation)
+    }
+    fn capsule(start: Vec2, end: Vec2, radius: f32) -> Collider {
+        Collider::Capsule { start, end, radius }
+    }
+
+    #[test]
+    fn boxes_with_touching_parallel_edges_intersect() {
+        let a = rect(Vec2::ZERO, Vec2::ONE, 0.);
+        assert!(a.intersects(&rect(Vec2::new(2., 0.), Vec2::ONE, 0.)));
+        assert!(!a.intersects(&rect(Vec2::new(2.1, 0.), Vec2::ONE, 0.)));
+    }
+
+    #[test]
+    fn boxes_turned_the_same_way_use_their_own_axes() {
+        let a = rect(Vec2::ZERO, Vec2::new(2., 0.5), FRAC_PI_4);
+        //along the long side they overlap, across it they don't
+        let along = Vec2::from_angle(FRAC_PI_4) * 3.5;
+        let across = Vec2::from_angle(FRAC_PI_4).perp() * 1.2;
+        assert!(a.intersects(&rect(along, Vec2::new(2., 0.5), FRAC_PI_4)));
+        assert!(!a.intersects(&rect(across, Vec2::new(2., 0.5), FRAC_PI_4)));
+    }
+
+    #[test]
+    fn rotated_box_is_separated_on_its_own_axis() {
+        let a = rect(Vec2::ZERO, Vec2::ONE, 0.);
+        //the bounding boxes overlap but the diamond's edge is past the corner
+        let b = rect(Vec2::splat(2.3), Vec2::ONE, FRAC_PI_4);
+        assert!(!a.intersects(&b));
+        assert!(!b.intersects(&a));
+        let c = rect(Vec2::splat(1.5), Vec2::ONE, FRAC_PI_4);
+        assert!(a.intersects(&c));
+    }
+
+    #[test]
+    fn rotated_box_and_circle() {
+        let a = rect(Vec2::ZERO, Vec2::ONE, FRAC_PI_4);
+        let corner = Vec2::new(0., 2f32.sqrt());
+        let circle = |center: Vec2| Collider::Circle {
+            center,
+            radius: 0.1,
+        };
+        assert!(a.intersects(&circle(corner + Vec2::Y * 0.05)));
+        assert!(!a.intersects(&circle(corner + Vec2::Y * 0.2)));
+    }
+
+    #[test]
+    fn parallel_capsules_use_the_gap_between_them() {
+        let a = capsule(Vec2::ZERO, Vec2::new(4., 0.), 0.4);
+        assert!(!a.intersects(&capsule(Vec2::new(1., 1.), Vec2::new(5., 1.), 0.4)));
+        assert!(a.intersects(&capsule(Vec2::new(1., 1.), Vec2::new(5., 1.), 0.6)));
+    }
+
+    #[test]
+    fn collinear_segments_overlap() {
+        let a = capsule(Vec2::ZERO, Vec2::new(2., 0.), 0.);
+        assert!(a.intersects(&capsule(Vec2::new(1., 0.), Vec2::new(3., 0.), 0.)));
+        assert!(!a.intersects(&capsule(Vec2::new(2.5, 0.), Vec2::new(3., 0.), 0.)));
+    }
+
+    #[test]
+    fn zero_length_capsule_acts_like_a_circle() {
+        let a = capsule(Vec2::ZERO, Vec2::ZERO, 1.);
+        let circle = Collider::Circle {
+            center: Vec2::new(1.5, 0.),
+            radius: 0.6,
+        };
+        assert!(a.intersects(&circle));
+        assert!(!a.intersects(&capsule(Vec2::new(3., 0.), Vec2::new(3., 0.), 1.)));
+        assert!(a.intersects(&capsule(Vec2::new(-1., 2.), Vec2::new(1., 2.), 1.)));
+        assert_eq!(
+            point_segment_distance(Vec2::new(3., 4.), Vec2::ZERO, Vec2::ZERO),
+            5.
+        );
+    }
+
+    #[test]
+    fn zero_length_segment_against_a_box() {
+        let a = rect(Vec2::ZERO, Vec2::ONE, 0.);
+        assert!(a.intersects(&capsule(Vec2::new(0.5, 0.5), Vec2::new(0.5, 0.5), 0.)));
+        assert!(!a.intersects(&capsule(Vec2::new(2., 0.), Vec2::new(2., 0.), 0.5)));
+        assert!(a.intersects(&capsule(Vec2::new(2., 0.), Vec2::new(2., 0.), 1.)));
+    }
+
+    #[test]
+    fn segment_through_a_box_intersects_without_its_ends_inside() {
+        let a = rect(Vec2::ZERO, Vec2::ONE, FRAC_PI_4);
+        let segment = Collider::new(HitboxShape::Segment, Vec2::ZERO, Vec2::new(5., 0.), 0.);
+        assert!(a.intersects(&segment));
+        assert!(segment.intersects(&a));
+        let above = Collider::new(
+            HitboxShape::Segment,
+            Vec2::new(0., 2.),
+            Vec2::new(5., 0.),
+            0.,
+        );
+        assert!(!a.intersects(&above));
+    }
+}
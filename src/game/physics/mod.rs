@@ -1,3 +1,5 @@
+pub mod collider;
+pub mod motion;
 pub mod physics;
 pub mod physics_object;
 pub mod rectangle;
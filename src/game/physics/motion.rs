@@ -0,0 +1,268 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::game::{
+    physics::physics_object::{PhysicsComponent, update_physics},
+    player::player::Player,
+};
+
+pub struct MotionPlugin;
+impl Plugin for MotionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, update_motion.before(update_physics));
+    }
+}
+
+#[derive(Clone)]
+pub enum MotionKind {
+    //speed is added along the heading and the heading turns each frame,
+    //gravity is added on top and drag takes a fraction of the velocity away
+    Accelerate {
+        //replaces the velocity when the segment starts
+        velocity: Option<Vec2>,
+        acceleration: f32,
+        //radians
+        angular_velocity: f32,
+        gravity: Vec2,
+        drag: f32,
+        //0 leaves the speed unlimited
+        max_speed: f32,
+    },
+    //turns towards the soul by at most turn_rate radians each frame
+    Homing {
+        speed: f32,
+        turn_rate: f32,
+    },
+    //bezier curve from the segment's start through control points relative to it
+    Bezier {
+        points: Vec<Vec2>,
+    },
+    //curve passing through every point, relative to the segment's start
+    CatmullRom {
+        points: Vec<Vec2>,
+    },
+    //circles a point relative to the segment's start, radius 0 keeps the
+    //distance the object starts at
+    Orbit {
+        center: Vec2,
+        radius: f32,
+        //radians each frame, counter clockwise
+        angular_speed: f32,
+    },
+}
+
+#[derive(Clone)]
+pub struct MotionSegment {
+    pub kind: MotionKind,
+    //seconds before the next segment starts, 0 never ends.
+    //curves are stretched over the duration so they need one
+    pub duration: f32,
+}
+
+//wave added across the movement, it keeps going from segment to segment
+#[derive(Clone)]
+pub struct SineOffset {
+    pub axis: Vec2,
+    pub amplitude: f32,
+    //waves per second
+    pub frequency: f32,
+    //radians
+    pub phase: f32,
+}
+
+//drives a PhysicsComponent through a chain of segments by setting its velocity,
+//when the last segment ends the object keeps its last velocity
+#[derive(Component, Clone, Default)]
+pub struct Motion {
+    pub segments: Vec<MotionSegment>,
+    pub sine: Option<SineOffset>,
+    //turns the hitbox and sprite to the direction of movement
+    pub face_velocity: bool,
+    //radians, used when accelerating from a standstill
+    pub heading: f32,
+
+    pub index: usize,
+    //seconds into the current segment and since the motion started
+    pub timer: f32,
+    pub time: f32,
+    //velocity without the sine offset
+    pub velocity: Vec2,
+    //position the current segment started at, without the sine offset
+    pub start: Vec2,
+    pub wave: Vec2,
+    pub orbit_angle: f32,
+    pub orbit_radius: f32,
+    pub started: bool,
+}
+
+impl Motion {
+    pub fn new(segments: Vec<MotionSegment>) -> Motion {
+        Motion {
+            segments,
+            ..Default::default()
+        }
+    }
+    pub fn finished(&self) -> bool {
+        self.index >= self.segments.len()
+    }
+    fn enter_segment(&mut self, position: Vec2) {
+        self.start = position;
+        self.timer = 0.;
+        let Some(segment) = self.segments.get(self.index) else {
+            return;
+        };
+        match &segment.kind {
+            MotionKind::Accelerate {
+                velocity: Some(velocity),
+                ..
+            } => {
+                self.velocity = *velocity;
+            }
+            MotionKind::Orbit { center, radius, .. } => {
+                let offset = -*center;
+                self.orbit_angle = offset.to_angle();
+                self.orbit_radius = *radius;
+                if *radius == 0. {
+                    self.orbit_radius = offset.length();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn bezier(points: &[Vec2], t: f32) -> Vec2 {
+    //de casteljau, the curve starts at the origin
+    let mut curve = vec![Vec2::ZERO];
+    curve.extend(points.iter().copied());
+    while curve.len() > 1 {
+        for i in 0..curve.len() - 1 {
+            curve[i] = curve[i].lerp(curve[i + 1], t);
+        }
+        curve.pop();
+    }
+    curve[0]
+}
+
+pub fn catmull_rom(points: &[Vec2], t: f32) -> Vec2 {
+    let mut curve = vec![Vec2::ZERO];
+    curve.extend(points.iter().copied());
+    if curve.len() < 2 {
+        return Vec2::ZERO;
+    }
+    let sections = curve.len() - 1;
+    let scaled = t.clamp(0., 1.) * sections as f32;
+    let section = (scaled as usize).min(sections - 1);
+    let t = scaled - section as f32;
+
+    //the ends are repeated so the curve reaches the first and last points
+    let p0 = curve[section.saturating_sub(1)];
+    let p1 = curve[section];
+    let p2 = curve[section + 1];
+    let p3 = curve[(section + 2).min(curve.len() - 1)];
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t * t
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t * t * t)
+}
+
+fn update_motion(
+    mut motion_query: Query<(&mut Motion, &mut PhysicsComponent), Without<Player>>,
+    player_query: Query<&PhysicsComponent, With<Player>>,
+    time: Res<Time<Fixed>>,
+) {
+    let player = player_query.single().ok().map(|p| p.position);
+    for (mut motion, mut physics) in motion_query.iter_mut() {
+        if !motion.started {
+            motion.started = true;
+            motion.velocity = physics.velocity;
+            let position = physics.position - motion.wave;
+            motion.enter_segment(position);
+        }
+        let delta = time.delta_secs();
+        motion.time += delta;
+        motion.timer += delta;
+        let base = physics.position - motion.wave;
+        let mut next = base + motion.velocity;
+
+        if let Some(segment) = motion.segments.get(motion.index).cloned() {
+            let mut t = 1.0;
+            if segment.duration > 0. {
+                t = (motion.timer / segment.duration).min(1.0);
+            }
+            match segment.kind {
+                MotionKind::Accelerate {
+                    acceleration,
+                    angular_velocity,
+                    gravity,
+                    drag,
+                    max_speed,
+                    ..
+                } => {
+                    let mut speed = motion.velocity.length();
+                    if speed > 0. {
+                        motion.heading = motion.velocity.to_angle();
+                    }
+                    motion.heading += angular_velocity;
+                    speed = (speed + acceleration).max(0.);
+                    let mut velocity = Vec2::from_angle(motion.heading) * speed + gravity;
+                    velocity *= 1.0 - drag;
+                    if max_speed > 0. {
+                        velocity = velocity.clamp_length_max(max_speed);
+                    }
+                    motion.velocity = velocity;
+                    next = base + velocity;
+                }
+                MotionKind::Homing { speed, turn_rate } => {
+                    if motion.velocity != Vec2::ZERO {
+                        motion.heading = motion.velocity.to_angle();
+                    }
+                    if let Some(player) = player {
+                        let wanted = (player - base).to_angle();
+                        let difference =
+                            (wanted - motion.heading + TAU / 2.0).rem_euclid(TAU) - TAU / 2.0;
+                        motion.heading += difference.clamp(-turn_rate, turn_rate);
+                    }
+                    motion.velocity = Vec2::from_angle(motion.heading) * speed;
+                    next = base + motion.velocity;
+                }
+                MotionKind::Bezier { points } => {
+                    next = motion.start + bezier(&points, t);
+                    motion.velocity = next - base;
+                }
+                MotionKind::CatmullRom { points } => {
+                    next = motion.start + catmull_rom(&points, t);
+                    motion.velocity = next - base;
+                }
+                MotionKind::Orbit {
+                    center,
+                    angular_speed,
+                    ..
+                } => {
+                    motion.orbit_angle += angular_speed;
+                    next = motion.start
+                        + center
+                        + Vec2::from_angle(motion.orbit_angle) * motion.orbit_radius;
+                    motion.velocity = next - base;
+                }
+            }
+            if segment.duration > 0. && motion.timer >= segment.duration {
+                motion.index += 1;
+                motion.enter_segment(next);
+            }
+        }
+
+        let mut wave = Vec2::ZERO;
+        if let Some(sine) = &motion.sine {
+            wave = sine.axis
+                * sine.amplitude
+                * (TAU * sine.frequency * motion.time + sine.phase).sin();
+        }
+        motion.wave = wave;
+        physics.velocity = next + wave - physics.position;
+        if motion.face_velocity && physics.velocity != Vec2::ZERO {
+            physics.rotation = physics.velocity.to_angle();
+        }
+    }
+}
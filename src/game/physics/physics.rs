@@ -1,10 +1,12 @@
 use bevy::prelude::*;
 
-use crate::game::physics::{physics_object::PhysicsLogicPlugin, timestep::TimestepPlugin};
+use crate::game::physics::{
+    motion::MotionPlugin, physics_object::PhysicsLogicPlugin, timestep::TimestepPlugin,
+};
 
 pub struct PhysicsPlugin;
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((TimestepPlugin, PhysicsLogicPlugin));
+        app.add_plugins((TimestepPlugin, PhysicsLogicPlugin, MotionPlugin));
     }
 }
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::game::physics::collider::{Collider, HitboxShape};
+
 pub struct PhysicsLogicPlugin;
 impl Plugin for PhysicsLogicPlugin {
     fn build(&self, app: &mut App) {
@@ -14,6 +16,12 @@ pub struct PhysicsComponent {
     pub velocity: Vec2,
     pub half_hitbox: Vec2,
     pub half_collision_box: Vec2,
+
+    //radians, turns the hitbox and the sprite
+    pub rotation: f32,
+    //radians added to the rotation each frame
+    pub angular_velocity: f32,
+    pub shape: HitboxShape,
 }
 
 impl Default for PhysicsComponent {
@@ -23,6 +31,9 @@ impl Default for PhysicsComponent {
             velocity: Vec2::ZERO,
             half_hitbox: Vec2::ZERO,
             half_collision_box: Vec2::ZERO,
+            rotation: 0.,
+            angular_velocity: 0.,
+            shape: HitboxShape::Box,
         }
     }
 }
@@ -44,20 +55,27 @@ impl PhysicsComponent {
             velocity: velocity,
             half_collision_box: half_collision_box,
             half_hitbox: half_hitbox,
+            ..Default::default()
         }
     }
+    pub fn collider(&self) -> Collider {
+        Collider::new(self.shape, self.position, self.half_hitbox, self.rotation)
+    }
 }
 
 fn snap_objects(mut query: Query<(&mut Transform, &mut PhysicsComponent)>) {
     for (mut t, mut p) in query.iter_mut() {
         t.translation.x = p.position.x.floor();
         t.translation.y = p.position.y.floor();
+        t.rotation = Quat::from_rotation_z(p.rotation);
     }
 }
 
-fn update_physics(mut query: Query<(&mut PhysicsComponent)>) {
+pub fn update_physics(mut query: Query<(&mut PhysicsComponent)>) {
     for (mut p) in query.iter_mut() {
         let v = p.velocity;
         p.position += v;
+        let w = p.angular_velocity;
+        p.rotation += w;
     }
 }
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::game::{
+    data::data::{Data, EquipSlot, ItemData},
+    state::state::AppState,
+};
+
+pub struct InventoryPlugin;
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Inventory>()
+            .add_systems(OnExit(AppState::Loading), fill_inventory);
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Inventory {
+    //names of the items, looked up in the asset manager
+    pub items: Vec<String>,
+    //index of the item picked in the ITEM menu
+    pub selected: usize,
+
+    //equipped items, kept out of the item list
+    pub weapon: String,
+    pub armor: String,
+}
+
+impl Inventory {
+    pub fn take(&mut self, index: usize) -> String {
+        self.items.remove(index)
+    }
+    //puts the item on, the piece it replaces takes its place in the list
+    pub fn equip(&mut self, index: usize, slot: EquipSlot) {
+        let equipped = match slot {
+            EquipSlot::Weapon => &mut self.weapon,
+            EquipSlot::Armor => &mut self.armor,
+        };
+        let old = std::mem::replace(equipped, self.items[index].clone());
+        if !old.is_empty() {
+            self.items[index] = old;
+        } else {
+            self.items.remove(index);
+        }
+    }
+    pub fn weapon<'a>(&self, items: &'a HashMap<String, ItemData>) -> Option<&'a ItemData> {
+        items.get(&self.weapon)
+    }
+    pub fn armor<'a>(&self, items: &'a HashMap<String, ItemData>) -> Option<&'a ItemData> {
+        items.get(&self.armor)
+    }
+    //AT added by the equipment
+    pub fn bonus_at(&self, items: &HashMap<String, ItemData>) -> i32 {
+        let weapon = self.weapon(items).map_or(0, |i| i.at);
+        let armor = self.armor(items).map_or(0, |i| i.at);
+        weapon + armor
+    }
+    //DF added by the equipment
+    pub fn bonus_df(&self, items: &HashMap<String, ItemData>) -> i32 {
+        let weapon = self.weapon(items).map_or(0, |i| i.df);
+        let armor = self.armor(items).map_or(0, |i| i.df);
+        weapon + armor
+    }
+}
+
+pub fn fill_inventory(mut inventory: ResMut<Inventory>, data: Res<Data>) {
+    inventory.items = data.game.player.inventory.clone();
+    inventory.selected = 0;
+    inventory.weapon = data.game.player.weapon.clone();
+    inventory.armor = data.game.player.armor.clone();
+}
@@ -1 +1,3 @@
+pub mod inventory;
 pub mod player;
+pub mod soul;
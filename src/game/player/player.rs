@@ -2,12 +2,13 @@ use bevy::prelude::*;
 
 use crate::game::{
     camera::render_layers::RenderLayerStorage,
-    data::data::Data,
+    data::data::{Data, LevelData, PlayerData, SoulMode},
     loading::loading::AssetManager,
     physics::physics_object::PhysicsComponent,
+    player::soul::{Platform, SoulMotion, SoulPlugin, move_blue, move_purple},
     scene::{
-        internal::bullet_board::BulletBoard, internal::menu::MenuState,
-        internal::stats::spawn_stats,
+        internal::bullet_board::BulletBoard, internal::dodging::constrain_player,
+        internal::menu::MenuState, internal::stats::spawn_stats,
     },
     state::state::AppState,
 };
@@ -21,7 +22,14 @@ impl Plugin for PlayerPlugin {
             invincibility: 0.,
             flash_animation: 0.,
             interval: 0.1,
+            name: String::new(),
+            lv: 0,
+            exp: 0,
+            gold: 0,
+            at: 0,
+            df: 0,
         })
+        .add_plugins(SoulPlugin)
         .add_systems(OnEnter(MenuState::Dodging), move_soul)
         .add_systems(OnEnter(AppState::Level), spawn_player.before(spawn_stats))
         .add_systems(
@@ -39,10 +47,59 @@ pub struct PlayerStats {
 
     pub flash_animation: f32,
     pub interval: f32,
+
+    pub name: String,
+    pub lv: i32,
+    pub exp: i32,
+    pub gold: i32,
+    pub at: i32,
+    pub df: i32,
+}
+
+impl PlayerStats {
+    //starts over at LV 1 with full health
+    pub fn reset(&mut self, player: &PlayerData) {
+        self.name = player.name.clone();
+        self.exp = 0;
+        self.gold = 0;
+        self.set_level(player, 1);
+        self.health = self.max_health;
+    }
+    //takes the max HP and AT/DF of a level from the table, levels past its end keep the last entry
+    pub fn set_level(&mut self, player: &PlayerData, lv: i32) {
+        self.lv = lv.max(1);
+        self.max_health = player.health;
+        self.at = player.at;
+        self.df = player.df;
+        let index = (self.lv as usize - 1).min(player.levels.len().saturating_sub(1));
+        if let Some(level) = player.levels.get(index) {
+            self.max_health = level.health;
+            self.at = level.at;
+            self.df = level.df;
+        }
+    }
+    //raises the LV for as long as there is enough EXP, returns whether it went up.
+    //the new max HP is added to the current health as well
+    pub fn level_up(&mut self, levels: &[LevelData]) -> bool {
+        let mut raised = false;
+        while (self.lv as usize) < levels.len() && self.exp >= levels[self.lv as usize].exp {
+            let level = &levels[self.lv as usize];
+            self.health += level.health - self.max_health;
+            self.max_health = level.health;
+            self.at = level.at;
+            self.df = level.df;
+            self.lv += 1;
+            raised = true;
+        }
+        raised
+    }
 }
 
-#[derive(Component)]
-pub struct Player {}
+#[derive(Component, Default)]
+pub struct Player {
+    //whether the soul was steered during the last fixed frame, checked by blue and orange bullets
+    pub moving: bool,
+}
 
 fn move_soul(
     mut bullet_board: Res<BulletBoard>,
@@ -60,8 +117,10 @@ fn spawn_player(
     render_layers: Res<RenderLayerStorage>,
     bullet_board: Res<BulletBoard>,
 ) {
-    player_stats.health = data.game.player.health;
-    player_stats.max_health = data.game.player.health;
+    //health carries over between battles started from the overworld
+    if player_stats.max_health == 0 {
+        player_stats.reset(&data.game.player);
+    }
     commands.spawn((
         Sprite {
             image: asset_manager.images[&data.game.player.sprite.clone()].clone(),
@@ -77,16 +136,20 @@ fn spawn_player(
             ),
         ),
         render_layers.pre.clone(),
-        Player {},
+        Player::default(),
+        SoulMode::Red,
+        SoulMotion::default(),
     ));
 }
 
 pub fn player_movement(
     input: Res<ButtonInput<KeyCode>>,
-    mut player_query: Query<(&mut Player, &mut PhysicsComponent)>,
+    mut player_query: Query<(&mut Player, &mut PhysicsComponent, &SoulMode, &mut SoulMotion)>,
+    platform_query: Query<(Entity, &Platform)>,
+    bullet_board: Res<BulletBoard>,
     data: Res<Data>,
 ) {
-    for (mut player, mut physics) in player_query.iter_mut() {
+    for (mut player, mut physics, soul_mode, mut motion) in player_query.iter_mut() {
         let mut horizontal = 0.;
         let mut vertical = 0.;
         if input.pressed(KeyCode::ArrowUp) {
@@ -101,7 +164,166 @@ pub fn player_movement(
         if input.pressed(KeyCode::ArrowLeft) {
             horizontal -= 1.;
         }
-        physics.position.x += horizontal * data.game.player.speed;
-        physics.position.y += vertical * data.game.player.speed;
+        //only the player's input counts, gravity and the board pushing the soul don't
+        player.moving = horizontal != 0. || vertical != 0.;
+        match soul_mode {
+            SoulMode::Red | SoulMode::Cyan => {
+                physics.position.x += horizontal * data.game.player.speed;
+                physics.position.y += vertical * data.game.player.speed;
+            }
+            SoulMode::Orange => {
+                if horizontal != 0. || vertical != 0. {
+                    motion.direction = Vec2::new(horizontal, vertical);
+                }
+                physics.position += motion.direction * data.game.player.speed;
+                player.moving = motion.direction != Vec2::ZERO;
+            }
+            SoulMode::Blue => {
+                move_blue(
+                    &mut physics,
+                    &mut motion,
+                    &input,
+                    horizontal,
+                    &platform_query,
+                    &bullet_board,
+                    &data,
+                );
+            }
+            SoulMode::Purple => {
+                move_purple(&mut physics, &mut motion, horizontal, vertical, &bullet_board, &data);
+            }
+            //the green soul stays where it is and turns its shield instead
+            SoulMode::Green => {
+                player.moving = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(lv: i32, exp: i32) -> PlayerStats {
+        PlayerStats {
+            health: 20,
+            max_health: 20,
+            invincibility: 0.,
+            flash_animation: 0.,
+            interval: 0.1,
+            name: String::new(),
+            lv,
+            exp,
+            gold: 0,
+            at: 0,
+            df: 0,
+        }
+    }
+
+    fn level(exp: i32, health: i32, at: i32, df: i32) -> LevelData {
+        LevelData {
+            exp,
+            health,
+            at,
+            df,
+        }
+    }
+
+    fn levels() -> Vec<LevelData> {
+        vec![
+            level(0, 20, 0, 0),
+            level(10, 24, 2, 1),
+            level(30, 28, 4, 2),
+            level(70, 32, 6, 3),
+        ]
+    }
+
+    fn player_data() -> PlayerData {
+        PlayerData {
+            health: 16,
+            at: 1,
+            df: 1,
+            levels: levels(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exp_below_the_threshold_keeps_the_level() {
+        let mut player = stats(1, 9);
+        assert!(!player.level_up(&levels()));
+        assert_eq!(player.lv, 1);
+        assert_eq!(player.max_health, 20);
+    }
+
+    #[test]
+    fn exp_at_the_threshold_raises_the_level() {
+        let mut player = stats(1, 10);
+        assert!(player.level_up(&levels()));
+        assert_eq!(player.lv, 2);
+        assert_eq!(player.max_health, 24);
+        assert_eq!(player.health, 24);
+        assert_eq!((player.at, player.df), (2, 1));
+    }
+
+    #[test]
+    fn enough_exp_raises_several_levels_at_once() {
+        let mut player = stats(1, 30);
+        player.health = 5;
+        assert!(player.level_up(&levels()));
+        assert_eq!(player.lv, 3);
+        assert_eq!(player.max_health, 28);
+        //only the max HP that was added is healed
+        assert_eq!(player.health, 13);
+    }
+
+    #[test]
+    fn the_level_stops_at_the_end_of_the_table() {
+        let mut player = stats(1, 1000);
+        assert!(player.level_up(&levels()));
+        assert_eq!(player.lv, 4);
+        assert!(!player.level_up(&levels()));
+        assert_eq!(player.lv, 4);
+    }
+
+    #[test]
+    fn set_level_takes_the_stats_from_the_table() {
+        let mut player = stats(1, 0);
+        player.set_level(&player_data(), 3);
+        assert_eq!(player.lv, 3);
+        assert_eq!(player.max_health, 28);
+        assert_eq!((player.at, player.df), (4, 2));
+    }
+
+    #[test]
+    fn set_level_past_the_table_keeps_the_last_entry() {
+        let mut player = stats(1, 0);
+        player.set_level(&player_data(), 9);
+        assert_eq!(player.lv, 9);
+        assert_eq!(player.max_health, 32);
+        assert_eq!((player.at, player.df), (6, 3));
+    }
+
+    #[test]
+    fn set_level_below_one_is_raised_to_one() {
+        let mut player = stats(3, 0);
+        player.set_level(&player_data(), 0);
+        assert_eq!(player.lv, 1);
+        assert_eq!(player.max_health, 20);
+        player.set_level(&player_data(), -2);
+        assert_eq!(player.lv, 1);
+    }
+
+    #[test]
+    fn set_level_without_a_table_uses_the_base_stats() {
+        let mut player = stats(1, 0);
+        let data = PlayerData {
+            levels: Vec::new(),
+            ..player_data()
+        };
+        player.set_level(&data, 2);
+        assert_eq!(player.lv, 2);
+        assert_eq!(player.max_health, 16);
+        assert_eq!((player.at, player.df), (1, 1));
     }
 }
@@ -0,0 +1,434 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+use crate::game::{
+    data::data::{Data, PlatformData, SoulMode},
+    loading::loading::AssetManager,
+    physics::{collider::Collider, physics_object::PhysicsComponent},
+    player::player::{Player, player_movement},
+    scene::internal::{
+        beam::Beam,
+        bullet_board::{BulletBoard, move_towards},
+        health::Damage,
+        helpers::{despawn::DespawnInMenu, menu_item::MenuItem},
+        menu::MenuState,
+        projectile::{BulletPool, Pooled},
+    },
+    sound::sound::SoundPlayer,
+    state::state::AppState,
+};
+
+pub struct SoulPlugin;
+impl Plugin for SoulPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SoulSwitch>().add_systems(
+            FixedUpdate,
+            (
+                switch_soul_mode.before(player_movement),
+                update_platforms.before(player_movement),
+                (update_shield, update_rails).after(player_movement),
+                show_soul_parts,
+            )
+                .run_if(in_state(AppState::Level)),
+        );
+    }
+}
+
+const SHIELD_THICKNESS: f32 = 4.0;
+const PLATFORM_THICKNESS: f32 = 4.0;
+const RAIL_THICKNESS: f32 = 1.0;
+const SHIELD_COLOR: Color = Color::srgb(0.0, 0.47, 1.0);
+const PLATFORM_COLOR: Color = Color::srgb(0.0, 0.75, 0.0);
+const RAIL_COLOR: Color = Color::srgb(0.5, 0.2, 0.55);
+//how far below the blue soul the floor is looked for while standing
+const GROUND_PROBE: f32 = 0.5;
+
+pub fn soul_color(mode: SoulMode) -> Color {
+    match mode {
+        SoulMode::Red => Color::srgb(1.0, 0.0, 0.0),
+        SoulMode::Blue => Color::srgb(0.0, 0.24, 1.0),
+        SoulMode::Orange => Color::srgb(0.99, 0.65, 0.0),
+        SoulMode::Green => Color::srgb(0.0, 0.75, 0.0),
+        SoulMode::Purple => Color::srgb(0.84, 0.21, 0.85),
+        SoulMode::Cyan => Color::srgb(0.26, 0.99, 1.0),
+    }
+}
+
+//mode the soul switches to at the start of the next frame, set by attacks
+#[derive(Resource, Default)]
+pub struct SoulSwitch {
+    pub queued: Option<SoulMode>,
+}
+
+impl SoulSwitch {
+    pub fn switch(&mut self, mode: SoulMode) {
+        self.queued = Some(mode);
+    }
+}
+
+//movement state of the soul that is kept between frames
+#[derive(Component, Default)]
+pub struct SoulMotion {
+    //vertical speed of the blue soul, positive is up
+    pub vertical_speed: f32,
+    pub grounded: bool,
+    //platform the blue soul is standing on
+    pub platform: Option<Entity>,
+    //direction the orange soul keeps moving in
+    pub direction: Vec2,
+    //rail the purple soul is on, counted from the bottom
+    pub rail: i32,
+}
+
+//blocks bullets in the direction the green soul is facing
+#[derive(Component)]
+pub struct Shield {
+    pub angle: f32,
+    pub target_angle: f32,
+}
+
+impl Shield {
+    pub fn direction(&self) -> Vec2 {
+        Vec2::from_angle(self.angle)
+    }
+    pub fn hitbox(&self, soul: Vec2, data: &Data) -> Collider {
+        let soul_data = &data.game.player.soul;
+        Collider::Box {
+            center: soul + self.direction() * soul_data.shield_distance,
+            half_size: Vec2::new(soul_data.shield_length / 2.0, SHIELD_THICKNESS / 2.0),
+            rotation: self.angle - FRAC_PI_2,
+        }
+    }
+}
+
+//one way platform for the blue soul, moves along with the board
+#[derive(Component)]
+pub struct Platform {
+    pub offset: Vec2,
+    pub velocity: Vec2,
+    pub half_width: f32,
+}
+
+impl Platform {
+    pub fn position(&self, bullet_board: &BulletBoard) -> Vec2 {
+        bullet_board.position + self.offset
+    }
+}
+
+#[derive(Component)]
+pub struct SoulRail {
+    pub index: i32,
+}
+
+//height of a purple rail, they are spread evenly over the board
+pub fn rail_y(index: i32, bullet_board: &BulletBoard, data: &Data) -> f32 {
+    let count = data.game.player.soul.rail_count.max(1);
+    bullet_board.position.y - bullet_board.height / 2.0
+        + bullet_board.height * (index + 1) as f32 / (count + 1) as f32
+}
+
+pub fn spawn_platform(commands: &mut Commands, platform: &PlatformData) {
+    commands.spawn((
+        Sprite::from_color(
+            PLATFORM_COLOR,
+            Vec2::new(platform.width, PLATFORM_THICKNESS),
+        ),
+        Transform::from_translation(Vec3::new(0., 0., -0.5)),
+        Platform {
+            offset: Vec2::new(platform.x, platform.y),
+            velocity: Vec2::new(platform.velocity_x, platform.velocity_y),
+            half_width: platform.width / 2.0,
+        },
+        DespawnInMenu,
+    ));
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn switch_soul_mode(
+    mut commands: Commands,
+    mut soul_switch: ResMut<SoulSwitch>,
+    mut player_query: Query<
+        (
+            &mut SoulMode,
+            &mut SoulMotion,
+            &mut Sprite,
+            &PhysicsComponent,
+        ),
+        With<Player>,
+    >,
+    parts_query: Query<Entity, Or<(With<Shield>, With<SoulRail>)>>,
+    mut sounds: ResMut<SoundPlayer>,
+    bullet_board: Res<BulletBoard>,
+    asset_manager: Res<AssetManager>,
+    data: Res<Data>,
+) {
+    let Some(mode) = soul_switch.queued.take() else {
+        return;
+    };
+    let Ok((mut soul_mode, mut motion, mut sprite, physics)) = player_query.single_mut() else {
+        return;
+    };
+    if *soul_mode != mode {
+        sounds.play_sound_once_local(asset_manager.sounds["soul_mode"].clone());
+    }
+    *soul_mode = mode;
+    *motion = SoulMotion::default();
+
+    //the red soul uses the original sprite, the others tint a white one
+    let alpha = sprite.color.alpha();
+    if mode == SoulMode::Red {
+        sprite.image = asset_manager.images[&data.game.player.sprite].clone();
+        sprite.color = Color::WHITE;
+    } else {
+        sprite.image = asset_manager.images[&data.game.player.soul.sprite].clone();
+        sprite.color = soul_color(mode);
+    }
+    sprite.color.set_alpha(alpha);
+
+    for e in parts_query.iter() {
+        commands.entity(e).despawn();
+    }
+    match mode {
+        SoulMode::Green => {
+            commands.spawn((
+                Sprite::from_color(
+                    SHIELD_COLOR,
+                    Vec2::new(data.game.player.soul.shield_length, SHIELD_THICKNESS),
+                ),
+                Transform::from_translation(physics.position.extend(1.0)),
+                Shield {
+                    angle: FRAC_PI_2,
+                    target_angle: FRAC_PI_2,
+                },
+                MenuItem,
+            ));
+        }
+        SoulMode::Purple => {
+            for i in 0..data.game.player.soul.rail_count {
+                commands.spawn((
+                    Sprite::from_color(RAIL_COLOR, Vec2::new(1.0, RAIL_THICKNESS)),
+                    Transform::from_translation(Vec3::new(0., 0., -0.5)),
+                    SoulRail { index: i },
+                    MenuItem,
+                ));
+            }
+            //start on the rail closest to the soul
+            let mut closest = f32::MAX;
+            for i in 0..data.game.player.soul.rail_count {
+                let distance = (rail_y(i, &bullet_board, &data) - physics.position.y).abs();
+                if distance < closest {
+                    closest = distance;
+                    motion.rail = i;
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+//movement of the blue soul, called from player_movement
+pub fn move_blue(
+    physics: &mut PhysicsComponent,
+    motion: &mut SoulMotion,
+    input: &ButtonInput<KeyCode>,
+    horizontal: f32,
+    platform_query: &Query<(Entity, &Platform)>,
+    bullet_board: &BulletBoard,
+    data: &Data,
+) {
+    let soul = &data.game.player.soul;
+    let half_height = data.game.player.sprite_size_y / 2.0;
+
+    if motion.grounded && input.pressed(KeyCode::ArrowUp) {
+        motion.vertical_speed = soul.jump_speed;
+        motion.grounded = false;
+        motion.platform = None;
+    }
+    //letting go of up cuts the jump short
+    if !input.pressed(KeyCode::ArrowUp) && motion.vertical_speed > soul.release_speed {
+        motion.vertical_speed = soul.release_speed;
+    }
+    if !motion.grounded {
+        motion.vertical_speed = (motion.vertical_speed - soul.gravity).max(-soul.max_fall_speed);
+    }
+
+    physics.position.x += horizontal * data.game.player.speed;
+
+    //standing platforms carry the soul along
+    if let Some(platform_entity) = motion.platform {
+        match platform_query.get(platform_entity) {
+            Ok((_, platform)) => {
+                let position = platform.position(bullet_board);
+                physics.position.x += platform.velocity.x;
+                if (physics.position.x - position.x).abs() > platform.half_width {
+                    motion.platform = None;
+                    motion.grounded = false;
+                } else {
+                    physics.position.y = position.y + PLATFORM_THICKNESS / 2.0 + half_height;
+                }
+            }
+            Err(_) => {
+                motion.platform = None;
+                motion.grounded = false;
+            }
+        }
+    }
+    if motion.platform.is_some() {
+        return;
+    }
+
+    let previous_bottom = physics.position.y - half_height;
+    physics.position.y += motion.vertical_speed;
+    let bottom = physics.position.y - half_height;
+
+    //the edges of the arenas are the floor when they push the soul up, so it
+    //can stand on slopes of a turned or polygon board
+    let probe = physics.position - Vec2::new(0., GROUND_PROBE);
+    motion.grounded = false;
+    if motion.vertical_speed <= 0. && bullet_board.constrain(probe, half_height).y > probe.y {
+        physics.position = bullet_board.constrain(physics.position, half_height);
+        motion.vertical_speed = 0.;
+        motion.grounded = true;
+        return;
+    }
+    if bullet_board.constrain(physics.position, half_height).y < physics.position.y {
+        motion.vertical_speed = motion.vertical_speed.min(0.);
+    }
+
+    if motion.vertical_speed > 0. {
+        return;
+    }
+    for (e, platform) in platform_query.iter() {
+        let position = platform.position(bullet_board);
+        let top = position.y + PLATFORM_THICKNESS / 2.0;
+        if previous_bottom >= top
+            && bottom <= top
+            && (physics.position.x - position.x).abs() <= platform.half_width
+        {
+            physics.position.y = top + half_height;
+            motion.vertical_speed = 0.;
+            motion.grounded = true;
+            motion.platform = Some(e);
+            return;
+        }
+    }
+}
+
+//movement of the purple soul, called from player_movement
+pub fn move_purple(
+    physics: &mut PhysicsComponent,
+    motion: &mut SoulMotion,
+    horizontal: f32,
+    vertical: f32,
+    bullet_board: &BulletBoard,
+    data: &Data,
+) {
+    let soul = &data.game.player.soul;
+    physics.position.x += horizontal * data.game.player.speed;
+
+    //holding up or down keeps hopping once the soul reaches a rail
+    let target = rail_y(motion.rail, bullet_board, data);
+    if (physics.position.y - target).abs() < 0.5 && vertical != 0. {
+        motion.rail = (motion.rail + vertical as i32).clamp(0, soul.rail_count.max(1) - 1);
+    }
+    let target = rail_y(motion.rail, bullet_board, data);
+    physics.position.y += move_towards(physics.position.y, target, soul.rail_speed);
+}
+
+fn update_platforms(
+    mut platform_query: Query<(&mut Platform, &mut Transform)>,
+    bullet_board: Res<BulletBoard>,
+) {
+    for (mut platform, mut transform) in platform_query.iter_mut() {
+        let velocity = platform.velocity;
+        platform.offset += velocity;
+        let position = platform.position(&bullet_board);
+        transform.translation.x = position.x.floor();
+        transform.translation.y = position.y.floor();
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_shield(
+    mut commands: Commands,
+    mut shield_query: Query<(&mut Shield, &mut Transform)>,
+    player_query: Query<&PhysicsComponent, With<Player>>,
+    //beams go through the shield
+    bullet_query: Query<(Entity, &PhysicsComponent, Has<Pooled>), (With<Damage>, Without<Beam>)>,
+    mut pool: ResMut<BulletPool>,
+    mut sounds: ResMut<SoundPlayer>,
+    input: Res<ButtonInput<KeyCode>>,
+    menu_state: Res<State<MenuState>>,
+    asset_manager: Res<AssetManager>,
+    data: Res<Data>,
+) {
+    let Ok((mut shield, mut transform)) = shield_query.single_mut() else {
+        return;
+    };
+    let Ok(physics) = player_query.single() else {
+        return;
+    };
+
+    if *menu_state.get() == MenuState::Dodging {
+        if input.pressed(KeyCode::ArrowUp) {
+            shield.target_angle = FRAC_PI_2;
+        } else if input.pressed(KeyCode::ArrowDown) {
+            shield.target_angle = -FRAC_PI_2;
+        } else if input.pressed(KeyCode::ArrowLeft) {
+            shield.target_angle = FRAC_PI_2 * 2.0;
+        } else if input.pressed(KeyCode::ArrowRight) {
+            shield.target_angle = 0.;
+        }
+    }
+    //turns the short way around
+    let turn = Vec2::from_angle(shield.angle).angle_to(Vec2::from_angle(shield.target_angle));
+    shield.angle += turn.clamp(
+        -data.game.player.soul.shield_turn_speed,
+        data.game.player.soul.shield_turn_speed,
+    );
+
+    let position = physics.position + shield.direction() * data.game.player.soul.shield_distance;
+    transform.translation.x = position.x.floor();
+    transform.translation.y = position.y.floor();
+    transform.rotation = Quat::from_rotation_z(shield.angle - FRAC_PI_2);
+
+    if *menu_state.get() != MenuState::Dodging {
+        return;
+    }
+    let hitbox = shield.hitbox(physics.position, &data);
+    for (e, bullet, pooled) in bullet_query.iter() {
+        if hitbox.intersects(&bullet.collider()) {
+            pool.remove(&mut commands, e, pooled);
+            sounds.play_sound_once_local(asset_manager.sounds["block"].clone());
+        }
+    }
+}
+
+fn update_rails(
+    mut rail_query: Query<(&SoulRail, &mut Transform, &mut Sprite)>,
+    bullet_board: Res<BulletBoard>,
+    data: Res<Data>,
+) {
+    for (rail, mut transform, mut sprite) in rail_query.iter_mut() {
+        transform.translation.x = bullet_board.position.x.floor();
+        transform.translation.y = rail_y(rail.index, &bullet_board, &data).floor();
+        sprite.custom_size = Some(Vec2::new(bullet_board.width, RAIL_THICKNESS));
+    }
+}
+
+//the shield and rails are only shown while dodging
+#[allow(clippy::type_complexity)]
+fn show_soul_parts(
+    mut parts_query: Query<&mut Visibility, Or<(With<Shield>, With<SoulRail>)>>,
+    menu_state: Res<State<MenuState>>,
+    bullet_board: Res<BulletBoard>,
+) {
+    let shown = *menu_state.get() == MenuState::Dodging && bullet_board.stable();
+    for mut visibility in parts_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+        if shown {
+            *visibility = Visibility::Visible;
+        }
+    }
+}
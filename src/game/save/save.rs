@@ -0,0 +1,390 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs,
+    path::PathBuf,
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{
+    data::data::{Data, PlayerData, SaveData},
+    overworld::{
+        map::CurrentMap,
+        walker::{Facing, Walker},
+    },
+    physics::physics_object::PhysicsComponent,
+    player::{
+        inventory::{Inventory, fill_inventory},
+        player::PlayerStats,
+    },
+    state::state::AppState,
+};
+
+pub struct SavePlugin;
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SaveManager>()
+            .init_resource::<Story>()
+            .add_systems(
+                OnExit(AppState::Loading),
+                load_on_start.after(fill_inventory),
+            )
+            .add_systems(PostUpdate, (write_save, read_save.after(write_save)));
+    }
+}
+
+//raised whenever the layout of the save file changes, older files are upgraded by `migrate`
+pub const SAVE_VERSION: i64 = 2;
+
+//what happened in the world so far, kept between battles and in the save file
+#[derive(Resource, Default)]
+pub struct Story {
+    pub kills: i32,
+    pub spares: i32,
+    //set by map objects and read by anything that wants to remember a choice
+    pub flags: HashMap<String, String>,
+}
+
+impl Story {
+    pub fn set_flag(&mut self, flag: &str, value: &str) {
+        self.flags.insert(flag.to_string(), value.to_string());
+    }
+    pub fn flag(&self, flag: &str) -> Option<&String> {
+        self.flags.get(flag)
+    }
+}
+
+//saving and loading happen at the end of the frame they are asked for
+#[derive(Resource, Default)]
+pub struct SaveManager {
+    save_queued: bool,
+    load_queued: bool,
+}
+
+impl SaveManager {
+    pub fn save(&mut self) {
+        self.save_queued = true;
+    }
+    //does nothing when there is no save file yet
+    pub fn load(&mut self) {
+        self.load_queued = true;
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SaveFile {
+    pub version: i64,
+
+    pub name: String,
+    pub lv: i32,
+    pub exp: i32,
+    pub health: i32,
+    pub gold: i32,
+    pub inventory: Vec<String>,
+    pub weapon: String,
+    pub armor: String,
+
+    //map the game continues on and where the walker stood when saving
+    pub map: String,
+    pub position: [f32; 2],
+
+    pub kills: i32,
+    pub spares: i32,
+    //battle objects that were already fought
+    pub triggered: Vec<String>,
+    pub flags: BTreeMap<String, String>,
+}
+
+//the file lives in the user's data directory so it survives reinstalling the game
+pub fn save_path(settings: &SaveData) -> Option<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home.map(|h| h.join("Library").join("Application Support"))
+    } else {
+        match env::var_os("XDG_DATA_HOME") {
+            Some(path) if !path.is_empty() => Some(PathBuf::from(path)),
+            _ => home.map(|h| h.join(".local").join("share")),
+        }
+    };
+    base.map(|b| b.join(&settings.directory).join(&settings.file))
+}
+
+pub fn read_save_file(settings: &SaveData, player: &PlayerData) -> Option<SaveFile> {
+    let path = save_path(settings)?;
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return None;
+    };
+    let mut table: toml::Table = match toml::from_str(&contents) {
+        Ok(table) => table,
+        Err(e) => {
+            log::error!("could not read save file {} : {}", path.display(), e);
+            return None;
+        }
+    };
+    migrate(&mut table, player);
+    match toml::Value::Table(table).try_into::<SaveFile>() {
+        Ok(save) => Some(save),
+        Err(e) => {
+            log::error!("could not read save file {} : {}", path.display(), e);
+            None
+        }
+    }
+}
+
+pub fn write_save_file(settings: &SaveData, save: &SaveFile) {
+    let Some(path) = save_path(settings) else {
+        log::error!("no data directory to save to");
+        return;
+    };
+    let contents = match toml::to_string_pretty(save) {
+        Ok(contents) => contents,
+        Err(e) => {
+            log::error!("could not write save file : {}", e);
+            return;
+        }
+    };
+    if let Some(directory) = path.parent()
+        && let Err(e) = fs::create_dir_all(directory)
+    {
+        log::error!("could not create {} : {}", directory.display(), e);
+        return;
+    }
+    match fs::write(&path, contents) {
+        Ok(_) => log::info!("saved to {}", path.display()),
+        Err(e) => log::error!("could not write save file {} : {}", path.display(), e),
+    }
+}
+
+//upgrades an older save one version at a time before it is read
+fn migrate(table: &mut toml::Table, player: &PlayerData) {
+    let mut version = table
+        .get("version")
+        .and_then(|v| v.as_integer())
+        .unwrap_or(0);
+    if version > SAVE_VERSION {
+        log::warn!(
+            "save file version {} is newer than {}, reading it anyway",
+            version,
+            SAVE_VERSION
+        );
+        return;
+    }
+    while version < SAVE_VERSION {
+        //files without a version were written before it was added and read the same as version 1
+        if version == 1 {
+            //equipment was added in version 2, older saves start with the starting gear
+            table.insert(
+                "weapon".to_string(),
+                toml::Value::String(player.weapon.clone()),
+            );
+            table.insert(
+                "armor".to_string(),
+                toml::Value::String(player.armor.clone()),
+            );
+        }
+        version += 1;
+        log::info!("upgraded save file to version {}", version);
+    }
+    table.insert("version".to_string(), toml::Value::Integer(version));
+}
+
+//items that aren't in the data anymore are dropped from the save
+fn known_item(name: &str, data: &Data) -> bool {
+    let known = data.game.items.iter().any(|item| item.name == name);
+    if !known {
+        log::warn!("dropped unknown item {} from the save file", name);
+    }
+    known
+}
+
+fn apply_save(
+    save: SaveFile,
+    player_stats: &mut PlayerStats,
+    inventory: &mut Inventory,
+    story: &mut Story,
+    current_map: &mut CurrentMap,
+    data: &Data,
+) {
+    player_stats.name = save.name.clone();
+    if save.name.is_empty() {
+        player_stats.name = data.game.player.name.clone();
+    }
+    player_stats.set_level(&data.game.player, save.lv);
+    player_stats.exp = save.exp;
+    player_stats.gold = save.gold;
+    player_stats.health = save.health.clamp(1, player_stats.max_health);
+
+    inventory.items = save
+        .inventory
+        .into_iter()
+        .filter(|name| known_item(name, data))
+        .collect();
+    inventory.selected = 0;
+    inventory.weapon = save.weapon;
+    if !inventory.weapon.is_empty() && !known_item(&inventory.weapon, data) {
+        inventory.weapon.clear();
+    }
+    inventory.armor = save.armor;
+    if !inventory.armor.is_empty() && !known_item(&inventory.armor, data) {
+        inventory.armor.clear();
+    }
+
+    story.kills = save.kills;
+    story.spares = save.spares;
+    story.flags = save.flags.into_iter().collect();
+
+    //the walker is placed back where it saved once the overworld is entered
+    if !data.game.overworld.map.is_empty() && data.assets.maps.contains(&save.map) {
+        current_map.path = save.map;
+        current_map.triggered = save.triggered.into_iter().collect();
+        current_map.return_position = Some((Vec2::from(save.position), Facing::Down));
+        current_map.steps_left = 0;
+    }
+}
+
+fn load_on_start(
+    mut player_stats: ResMut<PlayerStats>,
+    mut inventory: ResMut<Inventory>,
+    mut story: ResMut<Story>,
+    mut current_map: ResMut<CurrentMap>,
+    data: Res<Data>,
+) {
+    if !data.game.save.load_on_start {
+        return;
+    }
+    let Some(save) = read_save_file(&data.game.save, &data.game.player) else {
+        return;
+    };
+    log::info!("continuing from save file");
+    apply_save(
+        save,
+        &mut player_stats,
+        &mut inventory,
+        &mut story,
+        &mut current_map,
+        &data,
+    );
+}
+
+fn read_save(
+    mut save_manager: ResMut<SaveManager>,
+    mut player_stats: ResMut<PlayerStats>,
+    mut inventory: ResMut<Inventory>,
+    mut story: ResMut<Story>,
+    mut current_map: ResMut<CurrentMap>,
+    data: Res<Data>,
+) {
+    if !save_manager.load_queued {
+        return;
+    }
+    save_manager.load_queued = false;
+    let Some(save) = read_save_file(&data.game.save, &data.game.player) else {
+        return;
+    };
+    apply_save(
+        save,
+        &mut player_stats,
+        &mut inventory,
+        &mut story,
+        &mut current_map,
+        &data,
+    );
+}
+
+fn write_save(
+    mut save_manager: ResMut<SaveManager>,
+    walker_query: Query<&PhysicsComponent, With<Walker>>,
+    player_stats: Res<PlayerStats>,
+    inventory: Res<Inventory>,
+    story: Res<Story>,
+    current_map: Res<CurrentMap>,
+    data: Res<Data>,
+) {
+    if !save_manager.save_queued {
+        return;
+    }
+    save_manager.save_queued = false;
+
+    let mut position = Vec2::ZERO;
+    if let Ok(physics) = walker_query.single() {
+        position = physics.position;
+    }
+    let save = SaveFile {
+        version: SAVE_VERSION,
+        name: player_stats.name.clone(),
+        lv: player_stats.lv,
+        exp: player_stats.exp,
+        health: player_stats.health,
+        gold: player_stats.gold,
+        inventory: inventory.items.clone(),
+        weapon: inventory.weapon.clone(),
+        armor: inventory.armor.clone(),
+        map: current_map.path.clone(),
+        position: position.to_array(),
+        kills: story.kills,
+        spares: story.spares,
+        triggered: current_map.triggered.iter().cloned().collect(),
+        flags: story.flags.clone().into_iter().collect(),
+    };
+    write_save_file(&data.game.save, &save);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player() -> PlayerData {
+        PlayerData {
+            weapon: "Stick".to_string(),
+            armor: "Bandage".to_string(),
+            ..Default::default()
+        }
+    }
+
+    //reads a save the way read_save_file does after parsing the file
+    fn read(contents: &str) -> SaveFile {
+        let mut table: toml::Table = toml::from_str(contents).unwrap();
+        migrate(&mut table, &player());
+        toml::Value::Table(table).try_into::<SaveFile>().unwrap()
+    }
+
+    #[test]
+    fn save_without_a_version_is_upgraded_to_the_latest() {
+        let save = read("name = \"FRISK\"\nlv = 3\ninventory = [\"Monster Candy\"]\n");
+        assert_eq!(save.version, SAVE_VERSION);
+        assert_eq!(save.name, "FRISK");
+        assert_eq!(save.lv, 3);
+        assert_eq!(save.inventory, vec!["Monster Candy".to_string()]);
+        assert_eq!(save.weapon, "Stick");
+        assert_eq!(save.armor, "Bandage");
+    }
+
+    #[test]
+    fn version_1_save_gets_the_starting_equipment() {
+        let save = read("version = 1\nname = \"FRISK\"\nflags = { door = \"open\" }\n");
+        assert_eq!(save.version, SAVE_VERSION);
+        assert_eq!(save.flags.get("door").map(|f| f.as_str()), Some("open"));
+        assert_eq!(save.weapon, "Stick");
+        assert_eq!(save.armor, "Bandage");
+    }
+
+    #[test]
+    fn latest_save_keeps_its_equipment() {
+        let save = read("version = 2\nweapon = \"Toy Knife\"\narmor = \"Faded Ribbon\"\n");
+        assert_eq!(save.version, 2);
+        assert_eq!(save.weapon, "Toy Knife");
+        assert_eq!(save.armor, "Faded Ribbon");
+    }
+
+    #[test]
+    fn newer_save_is_left_alone() {
+        let save = read("version = 99\nname = \"FRISK\"\n");
+        assert_eq!(save.version, 99);
+        assert_eq!(save.name, "FRISK");
+        assert_eq!(save.weapon, "");
+    }
+}
@@ -3,39 +3,56 @@ use std::collections::HashMap;
 use bevy::{ecs::system::SystemId, prelude::*};
 
 use crate::game::{
+    animation::animation::Animator,
+    data::data::Data,
     loading::loading::AssetManager,
     physics::physics_object::PhysicsComponent,
+    player::{
+        inventory::Inventory,
+        player::{Player, PlayerStats},
+    },
     scene::{
-        attacks::{
-            AttacksPlugin, attack_1, enter_attack_1, enter_shovel_attack, shovel_attack,
-            spawn_shovels,
-        },
         internal::{
+            act::{act, check},
             attack::Attack,
+            attack_pattern::{PatternAttack, enter_pattern_attack, pattern_attack},
             bullet_board::BulletBoard,
-            death::restart_screen::RestartPlugin,
-            decisions::{Decision, DecisionMenu, Decisions, remove_decisions},
+            death::restart_screen::{RestartPlugin, restart_encounter},
+            decisions::{Decision, DecisionMenu, Decisions, init_decision_menu, remove_decisions},
             dodging::DodgingPhaseManager,
+            encounter::{Encounter, start_encounter},
             enemy_health::{EnemyHealthPlugin, manage_enemy_healthbar},
             health::DamagePlugin,
             helpers::{
-                despawn::{DespawnPlugin, despawn_objects},
+                despawn::{DespawnInMenu, DespawnInTime, DespawnPlugin, despawn_objects},
                 menu_item::MenuItem,
             },
+            items::use_item,
             menu::{MenuPlugin, MenuState},
             menu_transition::MenuTransition,
-            opponent::{Opponent, OpponentPlugin},
+            mercy::{SPARE_COLOR, flee, spare},
+            opponent::{DamageText, Opponent, OpponentPlugin},
             progress::{Progress, ProgressPlugin},
-            selection::MenuOption,
+            selection::{MenuOption, MenuSelect},
+            speech::SpeechBubble,
             text::TextBox,
+            turn_script::TurnPlan,
         },
     },
+    sound::sound::SoundPlayer,
+    state::state::AppState,
 };
 
 pub struct BattlePlugin;
 impl Plugin for BattlePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<BattleEvents>()
+            .add_systems(OnExit(AppState::Loading), start_encounter)
+            .add_systems(OnExit(AppState::Level), leave_battle)
+            .add_systems(
+                OnEnter(MenuState::Decision),
+                build_decision_menus.before(init_decision_menu),
+            )
             .add_plugins((MenuPlugin,));
     }
 }
@@ -44,89 +61,73 @@ impl Plugin for BattlePlugin {
 pub struct BattleEvents {
     pub events: HashMap<String, SystemId>,
     pub advance_attacks: SystemId,
-    pub attacks: Vec<Attack>,
+    //runs the planned attack once the enemy is done talking
+    pub start_attack: SystemId,
+    //attacks that are written as systems instead of attack patterns
+    pub attack_library: HashMap<String, Attack>,
+    pub enter_pattern_attack: SystemId,
+    pub pattern_attack: SystemId,
     pub despawn_projectiles: SystemId,
 }
 impl FromWorld for BattleEvents {
     fn from_world(world: &mut World) -> Self {
         let mut events = HashMap::new();
-        let mut attacks = vec![
-            Attack {
-                enter_attack: Some(world.register_system(enter_attack_1)),
-                init_attack: None,
-                attack: Some(world.register_system(attack_1)),
-                exit_attack: None,
-            },
-            Attack {
-                enter_attack: Some(world.register_system(enter_shovel_attack)),
-                init_attack: Some(world.register_system(spawn_shovels)),
-                attack: Some(world.register_system(shovel_attack)),
-                exit_attack: None,
-            },
-        ];
+        events.insert("fight".to_string(), world.register_system(start_fight));
+        events.insert(
+            "enemy_healthbar".to_string(),
+            world.register_system(manage_enemy_healthbar),
+        );
+        events.insert("check".to_string(), world.register_system(check));
+        events.insert("act".to_string(), world.register_system(act));
+        events.insert("item".to_string(), world.register_system(use_item));
+        events.insert("spare".to_string(), world.register_system(spare));
+        events.insert("flee".to_string(), world.register_system(flee));
+        events.insert("win_battle".to_string(), world.register_system(win_battle));
+        events.insert("end_battle".to_string(), world.register_system(end_battle));
+        events.insert(
+            "restart_encounter".to_string(),
+            world.register_system(restart_encounter),
+        );
+
+        //every attack currently comes from attacks.toml
+        let attack_library = HashMap::new();
 
         Self {
-            advance_attacks: world.register_system(enter_planned_attack),
+            advance_attacks: world.register_system(plan_enemy_turn),
+            start_attack: world.register_system(enter_planned_attack),
             events: events,
-            attacks: attacks,
+            attack_library: attack_library,
+            enter_pattern_attack: world.register_system(enter_pattern_attack),
+            pattern_attack: world.register_system(pattern_attack),
             despawn_projectiles: world.register_system(despawn_objects),
         }
     }
 }
+impl BattleEvents {
+    pub fn get_attack(&self, name: &String, data: &Data) -> Option<Attack> {
+        if self.attack_library.contains_key(name) {
+            return Some(self.attack_library[name].clone());
+        }
+        if data.attacks.attacks.iter().any(|a| a.name == *name) {
+            return Some(Attack {
+                enter_attack: Some(self.enter_pattern_attack),
+                init_attack: None,
+                attack: Some(self.pattern_attack),
+                exit_attack: None,
+                pattern: Some(name.clone()),
+            });
+        }
+        None
+    }
+}
 impl FromWorld for Decisions {
     fn from_world(world: &mut World) -> Self {
-        let mut menu = HashMap::new();
-
-        let mut fight_menu = DecisionMenu::default();
-        let mut act_menu = DecisionMenu::default();
-        let mut item_menu = DecisionMenu::default();
-        let mut mercy_menu = DecisionMenu::default();
-
-        fight_menu.left_column.push(Decision::new_with_hover(
-            "Biped Potato".to_string(),
-            world.register_system(start_fight),
-            world.register_system(manage_enemy_healthbar),
-        ));
-
-        let mut act_sub_menu = DecisionMenu::default();
-
-        act_sub_menu.left_column.push(Decision::new(
-            "Check".to_string(),
-            world.register_system(check),
-        ));
-        act_sub_menu.right_column.push(Decision::new(
-            "Talk".to_string(),
-            world.register_system(talk),
-        ));
-
-        act_menu.left_column.push(Decision::new_with_menu(
-            "Biped Potato".to_string(),
-            Some(act_sub_menu),
-        ));
-
-        item_menu.left_column.push(Decision::new(
-            "Monster Candy".to_string(),
-            world.register_system(item),
-        ));
-
-        mercy_menu.left_column.push(Decision::new(
-            "Spare".to_string(),
-            world.register_system(item),
-        ));
-
-        mercy_menu.left_column.push(Decision::new(
-            "Flee".to_string(),
-            world.register_system(item),
-        ));
-        menu.insert(MenuOption::Fight, fight_menu);
-        menu.insert(MenuOption::Act, act_menu);
-        menu.insert(MenuOption::Item, item_menu);
-        menu.insert(MenuOption::Mercy, mercy_menu);
-
         Self {
             remove_decisions: Some(world.register_system(remove_decisions)),
-            menu: menu,
+            menu: HashMap::new(),
             decision_menu: None,
+            pages: Vec::new(),
+            page: 0,
             menu_entities: default(),
             side: 0,
             selection: 0,
@@ -138,6 +139,76 @@ impl FromWorld for Decisions {
     }
 }
 
+//the menus list the enemies that are still fighting, so they are rebuilt every time they open
+fn build_decision_menus(
+    mut decisions: ResMut<Decisions>,
+    battle_events: Res<BattleEvents>,
+    encounter: Res<Encounter>,
+    progress: Res<Progress>,
+    inventory: Res<Inventory>,
+) {
+    let events = &battle_events.events;
+    let mut fight_menu = DecisionMenu::default();
+    let mut act_menu = DecisionMenu::default();
+    let mut mercy_menu = DecisionMenu::default();
+
+    let mut any_spareable = false;
+    let active = progress.active_enemies();
+    for index in active {
+        let enemy = encounter.get_enemy(index);
+        let mut color = Color::WHITE;
+        if encounter.spareable(index, &progress) {
+            color = SPARE_COLOR;
+            any_spareable = true;
+        }
+
+        fight_menu.left_column.push(
+            Decision::new_with_hover(
+                enemy.name.clone(),
+                events["fight"],
+                events["enemy_healthbar"],
+            )
+            .with_target(index)
+            .with_color(color),
+        );
+
+        let mut acts = vec![Decision::new("Check".to_string(), events["check"])];
+        for j in 0..enemy.acts.len() {
+            acts.push(Decision::new(enemy.acts[j].name.clone(), events["act"]).with_act(j));
+        }
+        let act_sub_menu = DecisionMenu::paginate(acts);
+
+        act_menu.left_column.push(
+            Decision::new_with_menu(enemy.name.clone(), Some(act_sub_menu))
+                .with_target(index)
+                .with_color(color),
+        );
+    }
+
+    let mut items = Vec::new();
+    for i in 0..inventory.items.len() {
+        items.push(Decision::new(inventory.items[i].clone(), events["item"]).with_item(i));
+    }
+    let item_menu = DecisionMenu::paginate(items);
+
+    let mut spare_color = Color::WHITE;
+    if any_spareable {
+        spare_color = SPARE_COLOR;
+    }
+    mercy_menu
+        .left_column
+        .push(Decision::new("Spare".to_string(), events["spare"]).with_color(spare_color));
+
+    mercy_menu
+        .left_column
+        .push(Decision::new("Flee".to_string(), events["flee"]));
+
+    decisions.menu.insert(MenuOption::Fight, fight_menu);
+    decisions.menu.insert(MenuOption::Act, act_menu);
+    decisions.menu.insert(MenuOption::Item, item_menu);
+    decisions.menu.insert(MenuOption::Mercy, mercy_menu);
+}
+
 fn start_fight(
     mut bullet_board: Res<BulletBoard>,
     mut text_box: ResMut<TextBox>,
@@ -147,54 +218,248 @@ fn start_fight(
     menu_transition.new_state(MenuState::Fight);
 }
 
-fn talk(
+pub fn spawn_opponent(
+    asset_manager: Res<AssetManager>,
+    encounter: Res<Encounter>,
+    mut commands: Commands,
+) {
+    for i in 0..encounter.data.enemies.len() {
+        let enemy = encounter.get_enemy(i);
+        //an enemy with a missing sprite is still fought, drawn with the default image
+        let image = asset_manager.images.get(&enemy.sprite).cloned();
+        if image.is_none() {
+            log::error!("enemy {} uses unknown sprite {}", enemy.name, enemy.sprite);
+        }
+        let mut sprite = Sprite {
+            image: image.unwrap_or_default(),
+            ..Default::default()
+        };
+        let atlas = asset_manager.atlases.get(&enemy.atlas);
+        if let Some(layout) = atlas {
+            sprite.texture_atlas = Some(TextureAtlas {
+                layout: layout.clone(),
+                index: 0,
+            });
+        } else if !enemy.atlas.is_empty() {
+            log::error!("enemy {} uses unknown atlas {}", enemy.name, enemy.atlas);
+        }
+        //falls back to the normal sprite when the hurt sprite is missing
+        let hurt_sprite = asset_manager.images.get(&enemy.hit.hurt_sprite).cloned();
+        if hurt_sprite.is_none() && !enemy.hit.hurt_sprite.is_empty() {
+            log::error!(
+                "enemy {} uses unknown hurt sprite {}",
+                enemy.name,
+                enemy.hit.hurt_sprite
+            );
+        }
+        let e = commands
+            .spawn((
+                sprite.clone(),
+                Transform::from_scale(Vec3::splat(2.0)),
+                PhysicsComponent::new(Vec2::ZERO),
+                Opponent {
+                    offset: Vec2::ZERO,
+                    index: i,
+                    hurt: false,
+                    sprite: sprite.image,
+                    hurt_sprite,
+                },
+                Name::new(enemy.name.clone()),
+                MenuItem,
+            ))
+            .id();
+        if atlas.is_some() && !enemy.animations.is_empty() {
+            let Some(animations) = asset_manager.animations.get(&enemy.animations) else {
+                log::error!(
+                    "enemy {} uses unknown animations {}",
+                    enemy.name,
+                    enemy.animations
+                );
+                continue;
+            };
+            commands.entity(e).insert(Animator {
+                current_animation: "idle".to_string(),
+                animation_bank: animations.clone(),
+                ..Default::default()
+            });
+        }
+    }
+}
+//picks the enemy's attack, letting it talk first if the script gave it something to say
+#[allow(clippy::too_many_arguments)]
+fn plan_enemy_turn(
     mut commands: Commands,
-    mut decisions: ResMut<Decisions>,
-    mut text_box: ResMut<TextBox>,
-    mut battle_events: ResMut<BattleEvents>,
     mut menu_transition: ResMut<MenuTransition>,
+    mut decisions: ResMut<Decisions>,
+    mut turn_plan: ResMut<TurnPlan>,
+    mut speech: ResMut<SpeechBubble>,
+    battle_events: Res<BattleEvents>,
     progress: Res<Progress>,
+    encounter: Res<Encounter>,
     asset_manager: Res<AssetManager>,
 ) {
     commands.run_system(decisions.remove_decisions.unwrap());
-    menu_transition.new_state(MenuState::Text);
-    text_box.queue_event(
-        asset_manager.dialogue_storage["talk"].clone(),
-        battle_events.advance_attacks,
-    );
-}
+    turn_plan.plan_attack(&encounter, &progress);
 
-pub fn spawn_opponent(asset_manager: Res<AssetManager>, mut commands: Commands) {
-    commands.spawn((
-        Sprite {
-            image: asset_manager.images["sprites/bipedpotato2x.png"].clone(),
-            ..Default::default()
-        },
-        Transform::from_scale(Vec3::splat(2.0)),
-        PhysicsComponent::new(Vec2::ZERO),
-        Opponent { offset: Vec2::ZERO },
-        MenuItem,
-    ));
+    //an enemy whose speech doesn't exist attacks without talking
+    let dialogue = turn_plan.speech.as_ref().and_then(|name| {
+        let dialogue = asset_manager.dialogue_storage.get(name);
+        if dialogue.is_none() {
+            log::warn!("no speech named {}", name);
+        }
+        dialogue
+    });
+    if let Some(dialogue) = dialogue {
+        menu_transition.new_state(MenuState::Speech);
+        speech.queue_event(
+            dialogue.clone(),
+            turn_plan.attacker,
+            battle_events.start_attack,
+        );
+    } else {
+        commands.run_system(battle_events.start_attack);
+    }
 }
+
 fn enter_planned_attack(
     mut commands: Commands,
     mut battle_events: ResMut<BattleEvents>,
     mut progress: ResMut<Progress>,
     mut menu_transition: ResMut<MenuTransition>,
-    mut bullet_board: ResMut<BulletBoard>,
-    mut decisions: ResMut<Decisions>,
     mut dodging_manager: ResMut<DodgingPhaseManager>,
-    asset_manager: Res<AssetManager>,
+    mut pattern_attack: ResMut<PatternAttack>,
+    turn_plan: Res<TurnPlan>,
+    encounter: Res<Encounter>,
+    data: Res<Data>,
 ) {
-    commands.run_system(decisions.remove_decisions.unwrap());
     menu_transition.new_state(MenuState::Dodging);
-    let mut attack = battle_events.attacks[progress.turns as usize].clone();
+
+    let mut attack = battle_events.get_attack(&turn_plan.attack, &data);
+    if attack.is_none() {
+        if !turn_plan.attack.is_empty() {
+            log::error!("no attack named {}", turn_plan.attack);
+        }
+        //falls back to the enemy's next attack that exists
+        let attacks = &encounter.get_enemy(turn_plan.attacker).attacks;
+        let start = attacks.iter().position(|a| *a == turn_plan.attack);
+        for i in 0..attacks.len() {
+            let index = (start.map_or(0, |s| s + 1) + i) % attacks.len();
+            attack = battle_events.get_attack(&attacks[index], &data);
+            if attack.is_some() {
+                break;
+            }
+        }
+    }
+    let Some(mut attack) = attack else {
+        //nothing to attack with, the dodging phase ends right away
+        dodging_manager.time = 0.;
+        dodging_manager.attack = None;
+        dodging_manager.init_attack = None;
+        progress.turns += 1;
+        return;
+    };
+    if attack.pattern.is_some() {
+        pattern_attack.queue(attack.pattern.clone().unwrap());
+    }
     attack.enter(&mut commands);
     dodging_manager.attack = attack.attack;
     dodging_manager.init_attack = attack.init_attack;
-    progress.turns = (progress.turns + 1) % battle_events.attacks.len() as i32;
+    progress.turns += 1;
 }
 
-fn item() {}
+//shown once every enemy has been killed or spared
+#[allow(clippy::too_many_arguments)]
+fn win_battle(
+    mut text_box: ResMut<TextBox>,
+    mut menu_transition: ResMut<MenuTransition>,
+    mut player_stats: ResMut<PlayerStats>,
+    mut sounds: ResMut<SoundPlayer>,
+    progress: Res<Progress>,
+    encounter: Res<Encounter>,
+    battle_events: Res<BattleEvents>,
+    asset_manager: Res<AssetManager>,
+    data: Res<Data>,
+) {
+    menu_transition.new_state(MenuState::Text);
+
+    //killed enemies give EXP and gold, spared ones only gold
+    let mut exp = 0;
+    let mut gold = 0;
+    for i in 0..progress.enemies.len() {
+        let enemy = encounter.get_enemy(i);
+        if progress.enemies[i].killed {
+            exp += enemy.exp;
+            gold += enemy.gold;
+        } else if progress.enemies[i].spared {
+            gold += enemy.gold;
+        }
+    }
+    player_stats.exp += exp;
+    player_stats.gold += gold;
+
+    let mut dialogue = asset_manager.dialogue_storage["victory"].clone();
+    for i in 0..dialogue.dialogue.len() {
+        dialogue.dialogue[i] = dialogue.dialogue[i]
+            .replace("{exp}", exp.to_string().as_str())
+            .replace("{gold}", gold.to_string().as_str());
+    }
+    if player_stats.level_up(&data.game.player.levels) {
+        sounds.play_sound_once_local(asset_manager.sounds["level_up"].clone());
+        //written under the rewards instead of on a page of its own
+        let level_up = &asset_manager.dialogue_storage["level_up"].dialogue;
+        if let Some(last) = dialogue.dialogue.last_mut() {
+            for line in level_up.iter() {
+                *last += "\n* ";
+                *last += line.as_str();
+            }
+        }
+    }
+    text_box.queue_event(dialogue, battle_events.events["end_battle"]);
+}
 
-fn check() {}
+fn end_battle(
+    mut menu_transition: ResMut<MenuTransition>,
+    mut state: ResMut<NextState<AppState>>,
+    data: Res<Data>,
+) {
+    //battles started from the overworld go back to it
+    if !data.game.overworld.map.is_empty() {
+        state.set(AppState::Overworld);
+        return;
+    }
+    menu_transition.new_state(MenuState::Restart);
+}
+
+//clears the battle so the next one starts from the selection menu
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn leave_battle(
+    mut commands: Commands,
+    battle_query: Query<
+        Entity,
+        (
+            Or<(
+                With<MenuItem>,
+                With<Player>,
+                With<DamageText>,
+                With<DespawnInMenu>,
+                With<DespawnInTime>,
+            )>,
+            Without<ChildOf>,
+        ),
+    >,
+    mut bullet_board: ResMut<BulletBoard>,
+    mut menu_transition: ResMut<MenuTransition>,
+    mut menu_select: ResMut<MenuSelect>,
+    mut dodging_manager: ResMut<DodgingPhaseManager>,
+    mut turn_plan: ResMut<TurnPlan>,
+    asset_manager: Res<AssetManager>,
+) {
+    for e in battle_query.iter() {
+        commands.entity(e).despawn();
+    }
+    bullet_board.absolute_board(asset_manager.board_layouts["selection"].clone());
+    menu_transition.new_menu = None;
+    menu_select.reset();
+    dodging_manager.time = 0.;
+    *turn_plan = TurnPlan::default();
+}
@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+
+use crate::game::{
+    data::data::DialogueSet,
+    loading::loading::AssetManager,
+    scene::{
+        battle::BattleEvents,
+        internal::{
+            decisions::Decisions, encounter::Encounter, menu::MenuState,
+            menu_transition::MenuTransition, progress::Progress, text::TextBox,
+        },
+    },
+};
+
+//every enemy can be checked, the stats line is built from the enemy's current stats
+pub fn check(
+    mut commands: Commands,
+    mut decisions: ResMut<Decisions>,
+    mut text_box: ResMut<TextBox>,
+    mut menu_transition: ResMut<MenuTransition>,
+    battle_events: Res<BattleEvents>,
+    encounter: Res<Encounter>,
+    progress: Res<Progress>,
+) {
+    commands.run_system(decisions.remove_decisions.unwrap());
+    menu_transition.new_state(MenuState::Text);
+
+    let enemy = encounter.get_target();
+    let stats = &progress.enemies[encounter.target];
+    let mut text = enemy.name.to_uppercase()
+        + " - AT "
+        + stats.at.to_string().as_str()
+        + " DF "
+        + stats.df.to_string().as_str();
+    if !enemy.description.is_empty() {
+        text += "\n* ";
+        text += enemy.description.as_str();
+    }
+
+    text_box.queue_event(
+        DialogueSet {
+            name: "check".to_string(),
+            dialogue: vec![text],
+        },
+        battle_events.advance_attacks,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn act(
+    mut commands: Commands,
+    mut decisions: ResMut<Decisions>,
+    mut text_box: ResMut<TextBox>,
+    mut menu_transition: ResMut<MenuTransition>,
+    mut progress: ResMut<Progress>,
+    battle_events: Res<BattleEvents>,
+    encounter: Res<Encounter>,
+    asset_manager: Res<AssetManager>,
+) {
+    commands.run_system(decisions.remove_decisions.unwrap());
+
+    let act = &encounter.get_target().acts[encounter.act];
+    let enemy = &mut progress.enemies[encounter.target];
+    enemy.at += act.at;
+    enemy.df += act.df;
+    enemy.mercy += act.mercy;
+    for i in 0..act.flags.len() {
+        progress.flags.insert(act.flags[i].clone());
+    }
+
+    //an ACT without text goes straight to the enemy's turn
+    let Some(dialogue) = asset_manager.dialogue_storage.get(&act.dialogue) else {
+        if !act.dialogue.is_empty() {
+            log::error!("no dialogue named {}", act.dialogue);
+        }
+        commands.run_system(battle_events.advance_attacks);
+        return;
+    };
+    menu_transition.new_state(MenuState::Text);
+    text_box.queue_event(dialogue.clone(), battle_events.advance_attacks);
+}
@@ -5,6 +5,9 @@ pub struct Attack {
     pub init_attack: Option<SystemId>,
     pub attack: Option<SystemId>,
     pub exit_attack: Option<SystemId>,
+
+    //name of the attack pattern to queue before entering, if data driven
+    pub pattern: Option<String>,
 }
 
 impl Attack {
@@ -0,0 +1,430 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::game::{
+    data::data::{AttackPattern, BoardEdge, EmitterAnchor, SpawnerData},
+    loading::loading::AssetManager,
+    physics::{
+        motion::{Motion, MotionKind, MotionSegment, SineOffset},
+        physics_object::PhysicsComponent,
+    },
+    player::{
+        player::Player,
+        soul::{SoulSwitch, spawn_platform},
+    },
+    scene::internal::{
+        beam::spawn_beam,
+        bullet_board::{BoardSettled, BulletBoard},
+        dodging::DodgingPhaseManager,
+        emitter::{EmitterFollow, spawn_emitter},
+        health::{Damage, damage_color},
+        helpers::despawn::DespawnInMenu,
+        projectile::BulletPool,
+    },
+};
+
+pub struct AttackPatternPlugin;
+impl Plugin for AttackPatternPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PatternAttack>();
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SpawnerState {
+    pub timer: f32,
+    pub waves: i32,
+    pub edge: usize,
+}
+
+//runtime for the attacks described in attacks.toml
+#[derive(Resource, Default)]
+pub struct PatternAttack {
+    pub queued: Option<String>,
+    pub pattern: Option<AttackPattern>,
+    pub spawners: Vec<SpawnerState>,
+    pub beams: Vec<SpawnerState>,
+    //seconds since the attack started
+    pub elapsed: f32,
+    //soul changes of the pattern that already happened
+    pub soul_changes: usize,
+}
+
+impl PatternAttack {
+    pub fn queue(&mut self, name: String) {
+        self.queued = Some(name);
+    }
+}
+
+pub fn enter_pattern_attack(
+    mut commands: Commands,
+    mut bullet_board: ResMut<BulletBoard>,
+    mut soul_switch: ResMut<SoulSwitch>,
+    mut dodge_manager: ResMut<DodgingPhaseManager>,
+    mut pattern_attack: ResMut<PatternAttack>,
+    player_query: Query<Entity, With<Player>>,
+    asset_manager: Res<AssetManager>,
+) {
+    if pattern_attack.queued.is_none() {
+        return;
+    }
+    let name = pattern_attack.queued.take().unwrap();
+    let Some(pattern) = asset_manager.attack_patterns.get(&name).cloned() else {
+        log::error!("no attack pattern named {}", name);
+        return;
+    };
+
+    //a misspelled board leaves the board where it is
+    match asset_manager.board_layouts.get(&pattern.board) {
+        Some(board) => bullet_board.transition_board(board.clone()),
+        None => log::error!("attack {} uses unknown board {}", name, pattern.board),
+    }
+    for i in 0..pattern.board_keyframes.len() {
+        let keyframe = &pattern.board_keyframes[i];
+        let Some(board) = asset_manager.board_layouts.get(&keyframe.board) else {
+            log::error!(
+                "attack {} keyframe {} uses unknown board {}",
+                name,
+                i,
+                keyframe.board
+            );
+            continue;
+        };
+        bullet_board.queue_board(
+            board.clone(),
+            keyframe.duration,
+            keyframe.easing,
+            keyframe.wait,
+        );
+    }
+    dodge_manager.time = pattern.duration;
+    soul_switch.switch(pattern.soul);
+    for i in 0..pattern.platforms.len() {
+        spawn_platform(&mut commands, &pattern.platforms[i]);
+    }
+    for i in 0..pattern.emitters.len() {
+        let emitter = &pattern.emitters[i];
+        let offset = Vec2::new(emitter.x, emitter.y);
+        match emitter.anchor {
+            EmitterAnchor::Board => {
+                spawn_emitter(&mut commands, emitter, offset, EmitterFollow::Board);
+            }
+            EmitterAnchor::Player => {
+                if let Ok(player) = player_query.single() {
+                    spawn_emitter(
+                        &mut commands,
+                        emitter,
+                        offset,
+                        EmitterFollow::Entity(player),
+                    );
+                }
+            }
+            EmitterAnchor::None => {
+                let start = bullet_board.target_position + offset;
+                spawn_emitter(&mut commands, emitter, start, EmitterFollow::None);
+            }
+        }
+    }
+
+    pattern_attack.spawners = pattern
+        .spawners
+        .iter()
+        .map(|s| SpawnerState {
+            timer: s.delay,
+            waves: 0,
+            edge: 0,
+        })
+        .collect();
+    pattern_attack.beams = pattern
+        .beams
+        .iter()
+        .map(|b| SpawnerState {
+            timer: b.delay,
+            waves: 0,
+            edge: 0,
+        })
+        .collect();
+    pattern_attack.elapsed = 0.;
+    pattern_attack.soul_changes = 0;
+    pattern_attack.pattern = Some(pattern);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn pattern_attack(
+    mut commands: Commands,
+    time: Res<Time<Fixed>>,
+    mut pattern_attack: ResMut<PatternAttack>,
+    mut soul_switch: ResMut<SoulSwitch>,
+    mut settled_events: EventReader<BoardSettled>,
+    mut pool: ResMut<BulletPool>,
+    player_query: Query<&PhysicsComponent, With<Player>>,
+    bullet_board: Res<BulletBoard>,
+    asset_manager: Res<AssetManager>,
+) {
+    //read before returning so settles from before the attack aren't counted
+    let settles = settled_events.read().count();
+    //the pattern is borrowed next to the state it drives instead of being cloned every tick
+    let PatternAttack {
+        pattern,
+        spawners,
+        beams,
+        elapsed,
+        soul_changes,
+        ..
+    } = &mut *pattern_attack;
+    let Some(pattern) = pattern.as_ref() else {
+        return;
+    };
+
+    *elapsed += time.delta_secs();
+    while *soul_changes < pattern.soul_changes.len() {
+        let change = &pattern.soul_changes[*soul_changes];
+        if change.time > *elapsed {
+            break;
+        }
+        soul_switch.switch(change.mode);
+        *soul_changes += 1;
+    }
+    for i in 0..pattern.spawners.len() {
+        let spawner = &pattern.spawners[i];
+        let state = &mut spawners[i];
+        let mut waves = 0;
+        if spawner.on_settle {
+            waves = settles;
+        } else {
+            state.timer -= time.delta_secs();
+            if state.timer <= 0. {
+                state.timer += spawner.interval;
+                waves = 1;
+            }
+        }
+        for _ in 0..waves {
+            if spawner.waves > 0 && state.waves >= spawner.waves {
+                break;
+            }
+            state.waves += 1;
+
+            let mut edge = None;
+            if !spawner.edges.is_empty() {
+                edge = Some(spawner.edges[state.edge]);
+                state.edge = (state.edge + 1) % spawner.edges.len();
+            }
+            spawn_wave(
+                &mut commands,
+                &mut pool,
+                &bullet_board,
+                &asset_manager,
+                spawner,
+                edge,
+            );
+        }
+    }
+    for i in 0..pattern.beams.len() {
+        let beam = &pattern.beams[i];
+        let state = &mut beams[i];
+        if beam.waves > 0 && state.waves >= beam.waves {
+            continue;
+        }
+        state.timer -= time.delta_secs();
+        if state.timer <= 0. {
+            state.timer += beam.interval;
+            state.waves += 1;
+
+            let origin = bullet_board.position + Vec2::new(beam.x, beam.y);
+            let mut angle = beam.angle.to_radians();
+            if beam.aim
+                && let Ok(player) = player_query.single()
+            {
+                angle = (player.position - origin).to_angle();
+            }
+            spawn_beam(&mut commands, origin, angle, beam);
+        }
+    }
+}
+
+fn spawn_wave(
+    commands: &mut Commands,
+    pool: &mut BulletPool,
+    bullet_board: &Res<BulletBoard>,
+    asset_manager: &Res<AssetManager>,
+    spawner: &SpawnerData,
+    edge: Option<BoardEdge>,
+) {
+    let mut velocity = Vec2::new(spawner.velocity_x, spawner.velocity_y);
+    let mut count = spawner.count;
+
+    //lines are laid out perpendicular to the direction the bullets travel or slide in
+    let slide = Vec2::new(spawner.slide_x, spawner.slide_y);
+    let dir = velocity.normalize_or(slide.normalize_or(Vec2::X));
+    let mut offset_dir = Vec2::new(-dir.y, dir.x);
+    let mut start = Vec2::new(spawner.x, spawner.y)
+        + offset_dir * spawner.spacing * (count - 1).max(0) as f32 / 2.0;
+
+    if let Some(edge) = edge {
+        let mut spawn_dir = Vec2::ZERO;
+        let mut distance = bullet_board.width;
+        let mut line_up_distance = bullet_board.height;
+        match edge {
+            BoardEdge::Left => {
+                spawn_dir = Vec2::new(-1., 0.);
+            }
+            BoardEdge::Top => {
+                spawn_dir = Vec2::new(0., 1.);
+                distance = bullet_board.height;
+                line_up_distance = bullet_board.width;
+            }
+            BoardEdge::Right => {
+                spawn_dir = Vec2::new(1., 0.);
+            }
+            BoardEdge::Bottom => {
+                spawn_dir = Vec2::new(0., -1.);
+                distance = bullet_board.height;
+                line_up_distance = bullet_board.width;
+            }
+        }
+        velocity = -spawn_dir * spawner.speed;
+        offset_dir = Vec2::new(spawn_dir.y, spawn_dir.x);
+        if count == 0 && spawner.spacing > 0. {
+            count = (line_up_distance / spawner.spacing).floor() as i32 - 1;
+        }
+        start = spawn_dir * distance / 2.0
+            + offset_dir * (line_up_distance / 2.0 - spawner.spacing);
+    }
+
+    //unknown sprites are reported when the attacks are loaded
+    let Some(image) = asset_manager.images.get(&spawner.sprite) else {
+        return;
+    };
+    let half_hitbox = Vec2::new(spawner.half_hitbox_x, spawner.half_hitbox_y);
+    let mut rotation = spawner.rotation.to_radians();
+    if spawner.face_velocity && velocity != Vec2::ZERO {
+        rotation += velocity.to_angle();
+    }
+    for i in 0..count {
+        if spawner.gaps.contains(&i) {
+            continue;
+        }
+        let pos = start - offset_dir * spawner.spacing * i as f32;
+        let mut spawn_pos = pos;
+        if spawner.slide_time > 0. {
+            spawn_pos -= slide;
+        }
+        let bullet = pool.spawn(
+            commands,
+            (
+                Sprite {
+                    image: image.clone(),
+                    flip_x: spawner.flip_x,
+                    flip_y: spawner.flip_y,
+                    color: damage_color(spawner.kind),
+                    ..Default::default()
+                },
+                Transform::from_translation(Vec2::ZERO.extend(-1.0)),
+                PhysicsComponent {
+                    rotation,
+                    angular_velocity: spawner.spin.to_radians(),
+                    shape: spawner.shape,
+                    ..PhysicsComponent::new_full(
+                        bullet_board.position + spawn_pos,
+                        velocity,
+                        half_hitbox,
+                        half_hitbox,
+                    )
+                },
+                DespawnInMenu,
+                Damage {
+                    damage: spawner.damage,
+                    kind: spawner.kind,
+                },
+            ),
+        );
+        if let Some(motion) = spawner_motion(spawner, pos, velocity) {
+            commands.entity(bullet).insert(motion);
+        }
+    }
+}
+
+//slide and wave of a spawned bullet, None when it only moves in a straight line
+fn spawner_motion(spawner: &SpawnerData, pos: Vec2, velocity: Vec2) -> Option<Motion> {
+    let slide = Vec2::new(spawner.slide_x, spawner.slide_y);
+    let sine = Vec2::new(spawner.sine_x, spawner.sine_y);
+    let sliding = spawner.slide_time > 0. && slide != Vec2::ZERO;
+    if !sliding && sine == Vec2::ZERO {
+        return None;
+    }
+    let mut segments = Vec::new();
+    if sliding {
+        segments.push(MotionSegment {
+            kind: MotionKind::Bezier {
+                points: vec![slide],
+            },
+            duration: spawner.slide_time,
+        });
+        segments.push(MotionSegment {
+            kind: MotionKind::Accelerate {
+                velocity: Some(velocity),
+                acceleration: 0.,
+                angular_velocity: 0.,
+                gravity: Vec2::ZERO,
+                drag: 0.,
+                max_speed: 0.,
+            },
+            duration: 0.,
+        });
+    }
+    let mut motion = Motion::new(segments);
+    if sine != Vec2::ZERO {
+        let mut phase = 0.;
+        if spawner.sine_wavelength > 0. {
+            phase = TAU * pos.x / spawner.sine_wavelength;
+        }
+        motion.sine = Some(SineOffset {
+            axis: sine,
+            amplitude: 1.0,
+            frequency: spawner.sine_frequency,
+            phase,
+        });
+    }
+    Some(motion)
+}
+
+//logs every board and sprite an attack names that isn't loaded, the parts using them are skipped
+pub fn validate_pattern(pattern: &AttackPattern, asset_manager: &AssetManager) -> bool {
+    let mut valid = true;
+    let mut boards = vec![&pattern.board];
+    for i in 0..pattern.board_keyframes.len() {
+        boards.push(&pattern.board_keyframes[i].board);
+    }
+    for board in boards {
+        if !asset_manager.board_layouts.contains_key(board) {
+            log::error!("attack {} uses unknown board {}", pattern.name, board);
+            valid = false;
+        }
+    }
+    let mut sprites = Vec::new();
+    for i in 0..pattern.spawners.len() {
+        sprites.push(&pattern.spawners[i].sprite);
+    }
+    for i in 0..pattern.emitters.len() {
+        sprites.push(&pattern.emitters[i].sprite);
+    }
+    for sprite in sprites {
+        if !asset_manager.images.contains_key(sprite) {
+            log::error!("attack {} uses unknown sprite {}", pattern.name, sprite);
+            valid = false;
+        }
+    }
+    //a single bullet doesn't need spacing, lines and filled edges do
+    for (i, spawner) in pattern.spawners.iter().enumerate() {
+        if spawner.count != 1 && spawner.spacing <= 0. {
+            log::error!(
+                "attack {} spawner {} needs a spacing above 0",
+                pattern.name,
+                i
+            );
+            valid = false;
+        }
+    }
+    valid
+}
@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+
+use crate::game::{
+    camera::shake::ScreenShake,
+    data::data::BeamData,
+    loading::loading::AssetManager,
+    physics::physics_object::PhysicsComponent,
+    scene::internal::{
+        health::{Damage, damage_color},
+        helpers::despawn::DespawnInMenu,
+    },
+    sound::sound::SoundPlayer,
+    state::state::AppState,
+};
+
+pub struct BeamPlugin;
+impl Plugin for BeamPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            update_beams.run_if(in_state(AppState::Level)),
+        );
+    }
+}
+
+const WARNING_WIDTH: f32 = 2.0;
+//seconds between each blink of the warning line
+const WARNING_BLINK: f32 = 0.1;
+
+//a long hitbox that warns where it will be before it fires
+#[derive(Component)]
+pub struct Beam {
+    pub data: BeamData,
+    pub timer: f32,
+    pub fired: bool,
+}
+
+//spawns a beam starting at `origin` pointing at `angle` in radians, the
+//angle of the data is only used by attack patterns
+pub fn spawn_beam(commands: &mut Commands, origin: Vec2, angle: f32, beam: &BeamData) {
+    let direction = Vec2::from_angle(angle);
+    let mut physics = PhysicsComponent::new(origin + direction * beam.length / 2.0);
+    physics.rotation = angle;
+    commands.spawn((
+        Sprite::from_color(
+            damage_color(beam.kind).with_alpha(0.5),
+            Vec2::new(beam.length, WARNING_WIDTH),
+        ),
+        Transform::from_translation(physics.position.extend(1.0)),
+        physics,
+        Beam {
+            data: beam.clone(),
+            timer: 0.,
+            fired: false,
+        },
+        DespawnInMenu,
+    ));
+}
+
+fn update_beams(
+    mut commands: Commands,
+    mut beam_query: Query<(Entity, &mut Beam, &mut PhysicsComponent, &mut Sprite)>,
+    mut shake: ResMut<ScreenShake>,
+    mut sounds: ResMut<SoundPlayer>,
+    time: Res<Time<Fixed>>,
+    asset_manager: Res<AssetManager>,
+) {
+    for (e, mut beam, mut physics, mut sprite) in beam_query.iter_mut() {
+        if beam.timer == 0. {
+            sounds.play_sound_once_local(asset_manager.sounds["beam_charge"].clone());
+        }
+        beam.timer += time.delta_secs();
+        let data = &beam.data;
+        let color = damage_color(data.kind);
+
+        //the warning line blinks until the beam fires
+        if beam.timer < data.charge_time {
+            let mut alpha = 0.5;
+            if (beam.timer / WARNING_BLINK) as i32 % 2 == 1 {
+                alpha = 0.2;
+            }
+            sprite.color = color.with_alpha(alpha);
+            continue;
+        }
+
+        let t = beam.timer - data.charge_time;
+        let mut width = data.width;
+        if t < data.grow_time {
+            width = data.width * t / data.grow_time;
+        } else if t > data.grow_time + data.fire_time {
+            let shrink = (t - data.grow_time - data.fire_time) / data.shrink_time.max(0.001);
+            if shrink >= 1.0 {
+                commands.entity(e).despawn();
+                continue;
+            }
+            width = data.width * (1.0 - shrink);
+        }
+
+        if !beam.fired {
+            beam.fired = true;
+            commands.entity(e).insert(Damage {
+                damage: beam.data.damage,
+                kind: beam.data.kind,
+            });
+            shake.shake(
+                beam.data.shake,
+                beam.data.grow_time + beam.data.fire_time,
+            );
+            sounds.play_sound_once_local(asset_manager.sounds["beam_fire"].clone());
+        }
+        sprite.color = color;
+        sprite.custom_size = Some(Vec2::new(beam.data.length, width.max(1.0)));
+        physics.half_hitbox = Vec2::new(beam.data.length / 2.0, width / 2.0);
+    }
+}
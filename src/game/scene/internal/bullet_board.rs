@@ -1,12 +1,22 @@
-use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
 
 use crate::game::{
     camera::render_layers::RenderLayerStorage,
-    data::data::{BoardLayout, Data},
-    loading::loading::AssetManager,
+    data::data::{ArenaLayout, BoardLayout, Data, Easing},
     physics::physics_object::PhysicsComponent,
     player::player::{Player, player_movement},
-    scene::internal::{helpers::menu_item::MenuItem, menu::MenuState},
+    scene::internal::{
+        helpers::{easing::ease, menu_item::MenuItem},
+        menu::MenuState,
+        scene::Resolution,
+    },
     state::state::AppState,
 };
 
@@ -24,26 +34,48 @@ impl Plugin for BulletBoardPlugin {
             target_position: Vec2::ZERO,
             border: 5.0,
 
+            rotation: 0.,
+            target_rotation: 0.,
+            spin: 0.,
+            points: rectangle(155., 130.),
+            target_points: rectangle(155., 130.),
+            arenas: Vec::new(),
+
             expansion_rate: 20.0,
             movement_rate: 1.0,
+            rotation_rate: 0.1,
+
+            tween: None,
+            keyframes: VecDeque::new(),
+            layout: String::new(),
+            settled: true,
 
             fill: None,
         })
+        .add_event::<BoardSettled>()
         .add_systems(PreUpdate, update_visibility.run_if(not_exception))
         .add_systems(OnEnter(AppState::Level), spawn_bullet_board)
         .add_systems(
             FixedPreUpdate,
             (
                 update_bullet_board,
-                update_side_fills,
+                update_board_mask,
                 update_bullet_board_fill,
-            ),
+            )
+                .chain(),
         );
     }
 }
 
-fn not_exception(menu_state: Res<State<MenuState>>) -> bool {
-    if *menu_state.get() == MenuState::Text || *menu_state.get() == MenuState::Fight {
+fn not_exception(menu_state: Option<Res<State<MenuState>>>) -> bool {
+    let Some(menu_state) = menu_state else {
+        return false;
+    };
+    if *menu_state.get() == MenuState::Text
+        || *menu_state.get() == MenuState::Speech
+        || *menu_state.get() == MenuState::Fight
+        || *menu_state.get() == MenuState::GameOver
+    {
         return false;
     }
     return true;
@@ -58,33 +90,250 @@ pub struct BulletBoard {
     pub target_height: f32,
     pub target_position: Vec2,
 
+    //radians, counter clockwise around the position
+    pub rotation: f32,
+    pub target_rotation: f32,
+    //radians turned each frame, the target rotation is ignored while spinning
+    pub spin: f32,
+    //corners of the box around the position before rotating, a rectangle of
+    //the width and height unless the layout gives a polygon
+    pub points: Vec<Vec2>,
+    pub target_points: Vec<Vec2>,
+    //extra boxes next to the main one, they are not animated between layouts
+    pub arenas: Vec<Arena>,
+
     pub border: f32,
 
     //how fast the dimensions of the box expands each frame
     pub expansion_rate: f32,
     //how fast the position of the box moves each frame
     pub movement_rate: f32,
+    //how fast the box turns to its target rotation each frame
+    pub rotation_rate: f32,
+
+    //timed move to the targets, the rates above are used when there is none
+    pub tween: Option<BoardTween>,
+    //layouts to move to one after the other once the targets are reached
+    pub keyframes: VecDeque<BoardKeyframe>,
+    //name of the layout the board is moving to or resting at
+    pub layout: String,
+    //whether the board reached the current layout and sent BoardSettled
+    pub settled: bool,
 
     pub fill: Option<Entity>,
 }
 
+//where a timed move started from, the end is the board's targets
+#[derive(Clone)]
+pub struct BoardTween {
+    pub start_width: f32,
+    pub start_height: f32,
+    pub start_position: Vec2,
+    pub start_rotation: f32,
+    pub start_points: Vec<Vec2>,
+    pub duration: f32,
+    pub timer: f32,
+    pub easing: Easing,
+}
+
+#[derive(Clone)]
+pub struct BoardKeyframe {
+    pub layout: BoardLayout,
+    //0 moves at the board's rates
+    pub duration: f32,
+    pub easing: Easing,
+    //seconds to hold the previous layout before moving
+    pub wait: f32,
+}
+
+//sent once the board reaches a layout, spinning boards count as reached once
+//everything but the rotation is in place
+#[derive(Event)]
+pub struct BoardSettled {
+    pub layout: String,
+}
+
+//a convex space the soul can move in apart from the main box
+#[derive(Clone, Default, PartialEq)]
+pub struct Arena {
+    pub position: Vec2,
+    pub rotation: f32,
+    pub spin: f32,
+    pub points: Vec<Vec2>,
+}
+
+impl Arena {
+    pub fn from_layout(layout: &ArenaLayout) -> Arena {
+        Arena {
+            position: Vec2::new(layout.x, layout.y),
+            rotation: layout.rotation.to_radians(),
+            spin: layout.spin.to_radians(),
+            points: layout_points(&layout.points, layout.width, layout.height),
+        }
+    }
+    //counter clockwise corners in the world
+    pub fn corners(&self) -> Vec<Vec2> {
+        place_points(&self.points, self.position, self.rotation)
+    }
+}
+
+//the black mask drawn over everything outside of the arenas
+#[derive(Component)]
+pub struct BoardMask {
+    //arenas the image was last drawn with
+    drawn: Vec<Vec<Vec2>>,
+    //pixels that were drawn last time, everything outside of it is black
+    rect: IRect,
+}
+
+//the mask is larger than the screen so the screen shake doesn't show its edges
+const MASK_MARGIN: f32 = 32.0;
+//times the soul is pushed back in, enough for the corners of any convex box
+const CONSTRAIN_PASSES: usize = 4;
+
+pub fn rectangle(width: f32, height: f32) -> Vec<Vec2> {
+    vec![
+        Vec2::new(-width / 2.0, -height / 2.0),
+        Vec2::new(width / 2.0, -height / 2.0),
+        Vec2::new(width / 2.0, height / 2.0),
+        Vec2::new(-width / 2.0, height / 2.0),
+    ]
+}
+
+fn layout_points(points: &[[f32; 2]], width: f32, height: f32) -> Vec<Vec2> {
+    if points.is_empty() {
+        return rectangle(width, height);
+    }
+    let mut corners: Vec<Vec2> = points.iter().map(|p| Vec2::new(p[0], p[1])).collect();
+    //the edge normals point outwards only when the corners go counter clockwise
+    let mut area = 0.;
+    for i in 0..corners.len() {
+        area += corners[i].perp_dot(corners[(i + 1) % corners.len()]);
+    }
+    if area < 0. {
+        corners.reverse();
+    }
+    corners
+}
+
+fn place_points(points: &[Vec2], position: Vec2, rotation: f32) -> Vec<Vec2> {
+    let turn = Vec2::from_angle(rotation);
+    points.iter().map(|p| position + turn.rotate(*p)).collect()
+}
+
+//how far a point is outside of a convex polygon, negative inside.
+//taking the furthest edge instead of the closest point keeps the corners sharp
+fn outside_distance(corners: &[Vec2], point: Vec2) -> f32 {
+    let mut distance = f32::MIN;
+    for i in 0..corners.len() {
+        let start = corners[i];
+        let end = corners[(i + 1) % corners.len()];
+        let normal = -(end - start).perp().normalize_or_zero();
+        distance = distance.max((point - start).dot(normal));
+    }
+    distance
+}
+
+//moves a point inside of a convex polygon so that it is `margin` away from every edge
+fn push_inside(corners: &[Vec2], point: Vec2, margin: f32) -> Vec2 {
+    let mut point = point;
+    for _ in 0..CONSTRAIN_PASSES {
+        for i in 0..corners.len() {
+            let start = corners[i];
+            let end = corners[(i + 1) % corners.len()];
+            let normal = -(end - start).perp().normalize_or_zero();
+            let overlap = (point - start).dot(normal) + margin;
+            if overlap > 0. {
+                point -= normal * overlap;
+            }
+        }
+    }
+    point
+}
+
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI
+}
+
 #[derive(Component)]
 pub struct BulletBoardFill;
 
 impl BulletBoard {
-    //stable means that all the targets line up with the actual values
-    pub fn stable(&mut self) -> bool {
+    //stable means that all the targets line up with the actual values,
+    //a spinning board counts as stable so the soul can move in it
+    pub fn stable(&self) -> bool {
         return (self.width.round() == self.target_width.round()
             && self.height.round() == self.target_height.round()
-            && Vec2::length(self.position - self.target_position) <= 0.1);
+            && Vec2::length(self.position - self.target_position) <= 0.1
+            && (self.spin != 0. || self.rotation == self.target_rotation)
+            && self.points == self.target_points);
     }
+    //moves to a layout using its duration and easing, cancelling queued keyframes
     pub fn transition_board(&mut self, board: BoardLayout) {
-        self.target_height = board.height;
-        self.target_width = board.width;
+        let duration = board.duration;
+        let easing = board.easing;
+        self.tween_board(board, duration, easing);
+    }
+    pub fn tween_board(&mut self, board: BoardLayout, duration: f32, easing: Easing) {
+        self.keyframes.clear();
+        self.move_to(board, duration, easing);
+    }
+    //moves to the layout after the ones already queued are reached
+    pub fn queue_board(&mut self, board: BoardLayout, duration: f32, easing: Easing, wait: f32) {
+        self.keyframes.push_back(BoardKeyframe {
+            layout: board,
+            duration,
+            easing,
+            wait,
+        });
+    }
+    fn move_to(&mut self, board: BoardLayout, duration: f32, easing: Easing) {
+        let points = layout_points(&board.points, board.width, board.height);
+        let size = layout_size(&board, &points);
+        self.target_width = size.x;
+        self.target_height = size.y;
         self.target_position = Vec2::new(board.x, board.y);
+
+        self.target_rotation = board.rotation.to_radians();
+        self.spin = board.spin.to_radians();
+        if self.spin == 0. {
+            //turn back the short way after spinning
+            self.rotation = self.target_rotation + wrap_angle(self.rotation - self.target_rotation);
+        }
+        //corners can only slide into place when there is the same number of them
+        if points.len() != self.points.len() {
+            self.points = points.clone();
+        }
+        self.target_points = points;
+        self.arenas = board.arenas.iter().map(Arena::from_layout).collect();
+
+        self.layout = board.name;
+        self.settled = false;
+        self.tween = None;
+        if duration > 0. {
+            self.tween = Some(BoardTween {
+                start_width: self.width,
+                start_height: self.height,
+                start_position: self.position,
+                start_rotation: self.rotation,
+                start_points: self.points.clone(),
+                duration,
+                timer: 0.,
+                easing,
+            });
+        }
     }
     pub fn absolute_board(&mut self, board: BoardLayout) {
-        self.set_absolute(board.width, board.height, Vec2::new(board.x, board.y));
+        let points = layout_points(&board.points, board.width, board.height);
+        let size = layout_size(&board, &points);
+        self.set_absolute(size.x, size.y, Vec2::new(board.x, board.y));
+        self.rotation = board.rotation.to_radians();
+        self.target_rotation = self.rotation;
+        self.spin = board.spin.to_radians();
+        self.points = points.clone();
+        self.target_points = points;
+        self.arenas = board.arenas.iter().map(Arena::from_layout).collect();
+        self.layout = board.name;
     }
     pub fn set_absolute(&mut self, width: f32, height: f32, position: Vec2) {
         self.width = width;
@@ -94,109 +343,54 @@ impl BulletBoard {
         self.target_width = width;
         self.target_height = height;
         self.target_position = position;
+
+        self.rotation = 0.;
+        self.target_rotation = 0.;
+        self.spin = 0.;
+        self.points = rectangle(width, height);
+        self.target_points = self.points.clone();
+        self.arenas.clear();
+
+        self.tween = None;
+        self.keyframes.clear();
+        self.layout = String::new();
+        self.settled = true;
     }
-    fn get_top(&mut self) -> Vec2 {
-        Vec2::new(
-            self.position.x,
-            self.position.y + self.height / 2.0 + self.border / 2.0,
-        )
-    }
-    fn get_bottom(&mut self) -> Vec2 {
-        Vec2::new(
-            self.position.x,
-            self.position.y - self.height / 2.0 - self.border / 2.0,
-        )
-    }
-    fn get_left(&mut self) -> Vec2 {
-        Vec2::new(
-            self.position.x - self.width / 2.0 - self.border / 2.0,
-            self.position.y,
-        )
-    }
-    fn get_right(&mut self) -> Vec2 {
-        Vec2::new(
-            self.position.x + self.width / 2.0 + self.border / 2.0,
-            self.position.y,
-        )
-    }
-
-    fn get_offset(&mut self, dir: Vec2, amount: f32) -> Vec2 {
-        return self.position + dir * amount;
-    }
-    fn get_side_fill(&mut self, side_fill: &SideFill) -> Vec2 {
-        match side_fill {
-            SideFill::Right => self.get_offset(Vec2::new(1., 0.), self.width / 2.0 + 1000.0 / 2.0),
-            SideFill::Left => self.get_offset(Vec2::new(-1., 0.), self.width / 2.0 + 1000.0 / 2.0),
-            SideFill::Top => self.get_offset(Vec2::new(0., 1.), self.height / 2.0 + 1000.0 / 2.0),
-            SideFill::Bottom => {
-                self.get_offset(Vec2::new(0., -1.), self.height / 2.0 + 1000.0 / 2.0)
-            }
+    //counter clockwise corners of the main box and every arena in the world
+    pub fn arena_corners(&self) -> Vec<Vec<Vec2>> {
+        let mut corners = vec![place_points(&self.points, self.position, self.rotation)];
+        for i in 0..self.arenas.len() {
+            corners.push(self.arenas[i].corners());
         }
+        corners
     }
-    fn get_vert_size(&mut self) -> Vec2 {
-        Vec2::new(self.width + self.border * 2.0, self.border)
-    }
-    fn get_hor_size(&mut self) -> Vec2 {
-        Vec2::new(self.border, self.height + self.border * 2.0)
-    }
-    fn get_border_position(&mut self, border: &BulletBoardBorder) -> Vec2 {
-        match border {
-            BulletBoardBorder::Right => self.get_right(),
-            BulletBoardBorder::Left => self.get_left(),
-            BulletBoardBorder::Top => self.get_top(),
-            BulletBoardBorder::Bottom => self.get_bottom(),
+    //whether a point is at least `margin` inside of any arena
+    pub fn contains(&self, point: Vec2, margin: f32) -> bool {
+        let corners = self.arena_corners();
+        for arena in &corners {
+            if outside_distance(arena, point) <= -margin {
+                return true;
+            }
         }
+        false
     }
-    fn get_border_scale(&mut self, border: &BulletBoardBorder) -> Vec2 {
-        match border {
-            BulletBoardBorder::Right => self.get_hor_size(),
-            BulletBoardBorder::Left => self.get_hor_size(),
-            BulletBoardBorder::Top => self.get_vert_size(),
-            BulletBoardBorder::Bottom => self.get_vert_size(),
+    //the closest point that is `margin` inside of an arena
+    pub fn constrain(&self, point: Vec2, margin: f32) -> Vec2 {
+        if self.contains(point, margin) {
+            return point;
         }
+        let corners = self.arena_corners();
+        let mut closest = point;
+        let mut distance = f32::MAX;
+        for arena in &corners {
+            let pushed = push_inside(arena, point, margin);
+            if pushed.distance(point) < distance {
+                distance = pushed.distance(point);
+                closest = pushed;
+            }
+        }
+        closest
     }
-    fn spawn_border(
-        &mut self,
-        commands: &mut Commands,
-        render_layers: &Res<RenderLayerStorage>,
-        border: BulletBoardBorder,
-    ) {
-        let mut scale = self.get_border_scale(&border);
-        let mut position = self.get_border_position(&border);
-        commands.spawn((
-            Sprite::from_color(Color::WHITE, Vec2::ONE),
-            Transform {
-                translation: Vec3::new(position.x, position.y, 0.0),
-                scale: Vec3::new(scale.x, scale.y, 1.),
-                ..Default::default()
-            },
-            border,
-            render_layers.pre.clone(),
-            MenuItem,
-        ));
-    }
-    fn spawn_side_fill(
-        &mut self,
-        commands: &mut Commands,
-        render_layers: &Res<RenderLayerStorage>,
-        side_fill: SideFill,
-    ) {
-        let mut scale = Vec2::splat(1000.);
-        let mut position = self.get_side_fill(&side_fill);
-        commands.spawn((
-            Sprite::from_color(Color::srgb(0.0, 0.0, 0.0), Vec2::ONE),
-            Transform {
-                translation: Vec3::new(position.x, position.y, -0.5),
-                scale: Vec3::new(scale.x, scale.y, 1.),
-                ..Default::default()
-            },
-            side_fill,
-            Name::new("Side"),
-            render_layers.pre.clone(),
-            MenuItem,
-        ));
-    }
-
     pub fn spawn_fill(&mut self, commands: &mut Commands, render_layers: &Res<RenderLayerStorage>) {
         let mut scale = Vec2::new(self.width, self.height);
         let mut position = self.position;
@@ -216,39 +410,52 @@ impl BulletBoard {
         self.fill = Some(entity);
     }
 }
-#[derive(Component, Default, PartialEq)]
-pub enum BulletBoardBorder {
-    #[default]
-    Right,
-    Left,
-    Top,
-    Bottom,
-}
-
-#[derive(Component, Default, PartialEq)]
-pub enum SideFill {
-    #[default]
-    Right,
-    Left,
-    Top,
-    Bottom,
+fn layout_size(board: &BoardLayout, points: &[Vec2]) -> Vec2 {
+    if board.width != 0. || board.height != 0. {
+        return Vec2::new(board.width, board.height);
+    }
+    let mut min = Vec2::MAX;
+    let mut max = Vec2::MIN;
+    for point in points {
+        min = min.min(*point);
+        max = max.max(*point);
+    }
+    max - min
 }
 
 pub fn spawn_bullet_board(
     mut commands: Commands,
-    mut bullet_board: ResMut<BulletBoard>,
-    asset_manager: Res<AssetManager>,
+    mut images: ResMut<Assets<Image>>,
+    resolution: Res<Resolution>,
     render_layers: Res<RenderLayerStorage>,
 ) {
-    bullet_board.spawn_border(&mut commands, &render_layers, BulletBoardBorder::Right);
-    bullet_board.spawn_border(&mut commands, &render_layers, BulletBoardBorder::Left);
-    bullet_board.spawn_border(&mut commands, &render_layers, BulletBoardBorder::Top);
-    bullet_board.spawn_border(&mut commands, &render_layers, BulletBoardBorder::Bottom);
-
-    bullet_board.spawn_side_fill(&mut commands, &render_layers, SideFill::Bottom);
-    bullet_board.spawn_side_fill(&mut commands, &render_layers, SideFill::Top);
-    bullet_board.spawn_side_fill(&mut commands, &render_layers, SideFill::Right);
-    bullet_board.spawn_side_fill(&mut commands, &render_layers, SideFill::Left);
+    let size = resolution.game_res + Vec2::splat(MASK_MARGIN * 2.0);
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size.x as u32,
+            height: size.y as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    commands.spawn((
+        Sprite {
+            image: images.add(image),
+            ..Default::default()
+        },
+        Transform::from_translation(Vec3::new(0., 0., -0.5)),
+        BoardMask {
+            drawn: Vec::new(),
+            rect: IRect::default(),
+        },
+        Name::new("Board mask"),
+        render_layers.pre.clone(),
+        MenuItem,
+    ));
 }
 pub fn move_towards_vec(start: Vec2, end: Vec2, rate: f32) -> Vec2 {
     let direction = Vec2::normalize_or_zero(end - start);
@@ -262,31 +469,97 @@ pub fn move_towards(start: f32, end: f32, rate: f32) -> f32 {
 
 fn update_bullet_board(
     mut bullet_board: ResMut<BulletBoard>,
-    mut border_query: Query<(&mut BulletBoardBorder, &mut Transform)>,
+    mut settled_events: EventWriter<BoardSettled>,
+    time: Res<Time<Fixed>>,
 ) {
-    bullet_board.width += move_towards(
-        bullet_board.width,
-        bullet_board.target_width,
-        bullet_board.expansion_rate,
-    );
-    bullet_board.height += move_towards(
-        bullet_board.height,
-        bullet_board.target_height,
-        bullet_board.expansion_rate,
-    );
+    let board = bullet_board.as_mut();
+    if let Some(mut tween) = board.tween.take() {
+        tween.timer += time.delta_secs();
+        if tween.timer < tween.duration {
+            let t = ease(tween.easing, tween.timer / tween.duration);
+            board.width = tween.start_width + (board.target_width - tween.start_width) * t;
+            board.height = tween.start_height + (board.target_height - tween.start_height) * t;
+            board.position = tween.start_position.lerp(board.target_position, t);
+            if board.spin == 0. {
+                board.rotation =
+                    tween.start_rotation + (board.target_rotation - tween.start_rotation) * t;
+            } else {
+                board.rotation = wrap_angle(board.rotation + board.spin);
+            }
+            for i in 0..board.points.len() {
+                board.points[i] = tween.start_points[i].lerp(board.target_points[i], t);
+            }
+            board.tween = Some(tween);
+        } else {
+            board.width = board.target_width;
+            board.height = board.target_height;
+            board.position = board.target_position;
+            if board.spin == 0. {
+                board.rotation = board.target_rotation;
+            } else {
+                board.rotation = wrap_angle(board.rotation + board.spin);
+            }
+            board.points = board.target_points.clone();
+        }
+    } else {
+        board.width += move_towards(
+            board.width,
+            board.target_width,
+            board.expansion_rate,
+        );
+        board.height += move_towards(
+            board.height,
+            board.target_height,
+            board.expansion_rate,
+        );
+
+        let position = board.position;
+        let target_position = board.target_position;
+        let movement_rate = board.movement_rate;
+        board.position += move_towards_vec(position, target_position, movement_rate);
 
-    let position = bullet_board.position;
-    let target_position = bullet_board.target_position;
-    let movement_rate = bullet_board.movement_rate;
-    bullet_board.position += move_towards_vec(position, target_position, movement_rate);
+        if board.spin != 0. {
+            board.rotation = wrap_angle(board.rotation + board.spin);
+        } else {
+            let rotation = board.rotation;
+            let target_rotation = board.target_rotation;
+            let rotation_rate = board.rotation_rate;
+            board.rotation += move_towards(rotation, target_rotation, rotation_rate);
+        }
+
+        //corners move half the expansion rate on each axis so a rectangle grows
+        //as fast as its width and height
+        let corner_rate = board.expansion_rate / 2.0;
+        for i in 0..board.points.len() {
+            let point = board.points[i];
+            let target = board.target_points[i];
+            board.points[i] += Vec2::new(
+                move_towards(point.x, target.x, corner_rate),
+                move_towards(point.y, target.y, corner_rate),
+            );
+        }
+    }
+    for i in 0..board.arenas.len() {
+        let arena = &mut board.arenas[i];
+        arena.rotation = wrap_angle(arena.rotation + arena.spin);
+    }
 
-    for (mut b, mut t) in border_query.iter_mut() {
-        let pos = bullet_board.get_border_position(&b);
-        t.translation.x = pos.x;
-        t.translation.y = pos.y;
-        let scale = bullet_board.get_border_scale(&b);
-        t.scale.x = scale.x;
-        t.scale.y = scale.y;
+    if !board.settled && board.tween.is_none() && board.stable() {
+        board.settled = true;
+        settled_events.write(BoardSettled {
+            layout: board.layout.clone(),
+        });
+    }
+    if !board.settled {
+        return;
+    }
+    let Some(keyframe) = board.keyframes.front_mut() else {
+        return;
+    };
+    keyframe.wait -= time.delta_secs();
+    if keyframe.wait <= 0. {
+        let keyframe = board.keyframes.pop_front().unwrap();
+        board.move_to(keyframe.layout, keyframe.duration, keyframe.easing);
     }
 }
 
@@ -301,15 +574,74 @@ fn update_bullet_board_fill(
     }
 }
 
-fn update_side_fills(
-    mut bullet_board: ResMut<BulletBoard>,
-    mut fill_query: Query<(&mut SideFill, &mut Transform)>,
+//redraws the mask image around the arenas whenever one moved, every pixel outside
+//of the arenas is black and the ones within `border` of an arena are the border
+fn update_board_mask(
+    bullet_board: Res<BulletBoard>,
+    mut mask_query: Query<(&mut BoardMask, &Sprite)>,
+    mut images: ResMut<Assets<Image>>,
 ) {
-    for (mut s, mut t) in fill_query.iter_mut() {
-        let pos = bullet_board.get_side_fill(&s);
-        t.translation.x = pos.x;
-        t.translation.y = pos.y;
+    let Ok((mut mask, sprite)) = mask_query.single_mut() else {
+        return;
+    };
+    let corners = bullet_board.arena_corners();
+    if mask.drawn == corners {
+        return;
+    }
+    let Some(image) = images.get_mut(&sprite.image) else {
+        return;
+    };
+    let width = image.width() as i32;
+    let height = image.height() as i32;
+    let Some(pixels) = image.data.as_mut() else {
+        return;
+    };
+    //only the area drawn last time has to be cleared
+    for y in mask.rect.min.y..mask.rect.max.y {
+        for x in mask.rect.min.x..mask.rect.max.x {
+            let index = ((y * width + x) * 4) as usize;
+            pixels[index..index + 4].copy_from_slice(&[0, 0, 0, 255]);
+        }
+    }
+
+    //only the pixels around the arenas can be anything but black
+    let border = bullet_board.border;
+    let mut min = Vec2::MAX;
+    let mut max = Vec2::MIN;
+    for corner in corners.iter().flatten() {
+        min = min.min(*corner);
+        max = max.max(*corner);
+    }
+    //the border reaches further than `border` past sharp corners
+    let reach = border * 4.0;
+    let origin = Vec2::new(-width as f32 / 2.0, height as f32 / 2.0);
+    let start_x = ((min.x - reach - origin.x).floor() as i32).clamp(0, width);
+    let end_x = ((max.x + reach - origin.x).ceil() as i32).clamp(0, width);
+    let start_y = ((origin.y - max.y - reach).floor() as i32).clamp(0, height);
+    let end_y = ((origin.y - min.y + reach).ceil() as i32).clamp(0, height);
+    for y in start_y..end_y {
+        for x in start_x..end_x {
+            let point = origin + Vec2::new(x as f32 + 0.5, -(y as f32 + 0.5));
+            let mut distance = f32::MAX;
+            for arena in &corners {
+                distance = distance.min(outside_distance(arena, point));
+            }
+            let mut color = [0, 0, 0, 255];
+            if distance <= 0. {
+                color = [0, 0, 0, 0];
+            } else if distance <= border {
+                color = [255, 255, 255, 255];
+            }
+            let index = ((y * width + x) * 4) as usize;
+            pixels[index..index + 4].copy_from_slice(&color);
+        }
     }
+    mask.drawn = corners;
+    //no arenas leave an empty rect instead of a flipped one
+    mask.rect = IRect {
+        min: IVec2::new(start_x, start_y),
+        max: IVec2::new(end_x.max(start_x), end_y.max(start_y)),
+    };
 }
 fn update_visibility(
     mut bullet_board: ResMut<BulletBoard>,
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use rand::{thread_rng, Rng};
 
-use crate::game::{data::data::Data, loading::loading::AssetManager, physics::physics_object::PhysicsComponent, player::player::Player, scene::internal::{helpers::despawn::{DespawnInTime, OpacityFromTimer}, menu::MenuState, menu_transition::MenuTransition, opponent::Opponent}, sound::sound::SoundPlayer};
+use crate::game::{data::data::Data, loading::loading::AssetManager, physics::physics_object::PhysicsComponent, player::player::Player, save::save::Story, scene::{battle::BattleEvents, internal::{encounter::Encounter, helpers::despawn::{DespawnInTime, OpacityFromTimer}, menu::MenuState, menu_transition::MenuTransition, opponent::Opponent, progress::Progress}}, sound::sound::SoundPlayer};
 
 pub struct EnemyDeathPlugin;
 impl Plugin for EnemyDeathPlugin {
@@ -20,6 +20,7 @@ pub struct DeathActivator {
     pub timer : f32,
     pub i : i32,
     pub death_time : f32,
+    pub finished : bool,
 }
 
 fn hide_player(mut player_query: Query<(&mut Visibility), With<Player>>) {
@@ -34,13 +35,22 @@ fn show_player(mut player_query: Query<(&mut Visibility), With<Player>>) {
     }
 }
 fn update_death_timer(
+    mut commands : Commands,
     mut d_a : ResMut<DeathActivator>,
     mut menu_transition : ResMut<MenuTransition>,
     mut time : Res<Time<Fixed>>,
+    battle_events : Res<BattleEvents>,
+    progress : Res<Progress>,
 ) {
     d_a.death_time -= time.delta_secs();
-    if d_a.death_time <= 0. {
-        menu_transition.new_state(MenuState::Restart);
+    if d_a.death_time <= 0. && !d_a.finished {
+        d_a.finished = true;
+        //the remaining enemies still get their turn
+        if progress.finished() {
+            commands.run_system(battle_events.events["win_battle"]);
+        } else {
+            commands.run_system(battle_events.advance_attacks);
+        }
     }
 }
 fn activate_dust(
@@ -53,7 +63,7 @@ fn activate_dust(
     d_a.timer -= time.delta_secs();
     if d_a.timer <= 0. && d_a.i < d_a.rows.len() as i32 {
         let mut rand = thread_rng();
-        d_a.timer = data.game.opponent_data.dust_time - d_a.timer.abs();
+        d_a.timer = data.game.enemy_death.dust_time - d_a.timer.abs();
         let l = d_a.rows[d_a.i as usize].len();
         let row= &d_a.rows[d_a.i as usize];
         for i in 0..l {
@@ -64,7 +74,7 @@ fn activate_dust(
                 let speed = 1.0;
                 let velocity = Vec2::new(v_x,v_y).normalize_or(Vec2::new(1.0,0.0));
                 p.velocity = velocity * speed;
-                commands.entity(row[i]).insert(DespawnInTime::new(data.game.opponent_data.dust_life,None));
+                commands.entity(row[i]).insert(DespawnInTime::new(data.game.enemy_death.dust_life,None));
             }
         }
         d_a.i+=1;
@@ -75,24 +85,43 @@ fn kill_enemy_visual(
     mut commands : Commands,
     mut opponent_query : Query<(&mut Opponent,&mut Sprite,&mut Transform,&mut Visibility)>,
     mut images : ResMut<Assets<Image>>,
+    layouts : Res<Assets<TextureAtlasLayout>>,
     mut sounds : ResMut<SoundPlayer>,
+    mut progress : ResMut<Progress>,
+    mut story : ResMut<Story>,
+    encounter : Res<Encounter>,
     asset_manager : Res<AssetManager>,
     data : Res<Data>,
 ) {
-    if let Ok((mut o, mut s,mut t,mut v)) = opponent_query.single_mut() {
+    for (mut o, mut s,mut t,mut v) in opponent_query.iter_mut() {
+        if o.index != encounter.target {
+            continue;
+        }
+        progress.enemies[o.index].killed = true;
+        story.kills += 1;
         sounds.play_sound_once_local(asset_manager.sounds["dust"].clone());
         *v = Visibility::Hidden;
         d_a.rows.clear();
-        d_a.death_time = data.game.opponent_data.death_time;
+        d_a.death_time = data.game.enemy_death.death_time;
+        d_a.finished = false;
         d_a.i = 0;
-        if let Some(mut image) = images.get_mut(&s.image) {
-            let width = image.width();
-            let height = image.height();
+        //the dust is made from the enemy's own sprite, not the hurt one
+        let handle = asset_manager.images[&encounter.get_enemy(o.index).sprite].clone();
+        if let Some(mut image) = images.get_mut(&handle) {
+            //enemies drawn from an atlas only turn their current frame to dust
+            let mut rect = URect::new(0, 0, image.width(), image.height());
+            if let Some(atlas) = &s.texture_atlas
+                && let Some(layout) = layouts.get(&atlas.layout)
+            {
+                rect = layout.textures[atlas.index];
+            }
+            let width = rect.width();
+            let height = rect.height();
 
             for y in (0..height).rev() {
                 let mut row = Vec::new();
                 for x in 0..width {
-                    let pixel_bytes = image.pixel_bytes_mut(UVec3::new(x, height - y - 1, 0)).unwrap();
+                    let pixel_bytes = image.pixel_bytes_mut(UVec3::new(rect.min.x + x, rect.min.y + height - y - 1, 0)).unwrap();
                     if pixel_bytes[3] > 0 {
                         let pos = Vec2::new(t.translation.x,t.translation.y) - Vec2::new(width as f32 * 2.0  / 2.0 , height as f32 * 2.0 / 2.0) + Vec2::splat(1.0) + Vec2::new(x as f32, y as f32) * 2.0; 
                         
@@ -0,0 +1,340 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use rand::{Rng, thread_rng};
+
+use crate::game::{
+    camera::render_layers::RenderLayerStorage,
+    data::data::{Data, SoulMode},
+    loading::loading::AssetManager,
+    physics::{
+        motion::{Motion, MotionKind, MotionSegment},
+        physics_object::PhysicsComponent,
+    },
+    player::{
+        player::{Player, PlayerStats},
+        soul::SoulSwitch,
+    },
+    save::save::SaveManager,
+    scene::{
+        battle::BattleEvents,
+        internal::{
+            helpers::{
+                despawn::{DespawnInMenu, DespawnInTime, OpacityFromTimer},
+                menu_item::MenuItem,
+            },
+            menu::MenuState,
+        },
+    },
+    sound::sound::{Song, SoundPlayer},
+    state::state::AppState,
+};
+
+pub struct GameOverPlugin;
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameOver>()
+            .add_systems(OnEnter(MenuState::GameOver), start_game_over)
+            .add_systems(OnExit(MenuState::GameOver), clear_game_over)
+            .add_systems(
+                Update,
+                continue_game_over.run_if(in_state(MenuState::GameOver)),
+            )
+            .add_systems(
+                FixedUpdate,
+                update_game_over.run_if(in_state(MenuState::GameOver)),
+            );
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum GameOverPhase {
+    //the soul stays where it was hit
+    #[default]
+    Freeze,
+    //the soul cracks
+    Break,
+    //the shards fly and fall
+    Shatter,
+    //GAME OVER fades in
+    Title,
+    //the message is typed out
+    Message,
+    //waits for Z
+    Wait,
+    //everything fades out
+    Leave,
+    Done,
+}
+
+#[derive(Resource, Default)]
+pub struct GameOver {
+    pub phase: GameOverPhase,
+    pub timer: f32,
+    pub message: String,
+    //letters of the message shown so far
+    pub typed: usize,
+    pub title: Option<Entity>,
+    pub text: Option<Entity>,
+}
+
+//despawned when the game over screen is left
+#[derive(Component)]
+pub struct GameOverItem;
+
+const TITLE_Y: f32 = 80.0;
+const MESSAGE_Y: f32 = -60.0;
+const SHARD_SIZE: f32 = 2.0;
+
+#[allow(clippy::too_many_arguments)]
+fn start_game_over(
+    mut commands: Commands,
+    mut game_over: ResMut<GameOver>,
+    mut player_stats: ResMut<PlayerStats>,
+    mut player_query: Query<(&mut PhysicsComponent, &mut Visibility), With<Player>>,
+    mut menu_query: Query<&mut Visibility, (With<MenuItem>, Without<Player>)>,
+    despawn_query: Query<Entity, (With<DespawnInMenu>, Without<Player>)>,
+    battle_events: Res<BattleEvents>,
+    data: Res<Data>,
+) {
+    for mut v in menu_query.iter_mut() {
+        *v = Visibility::Hidden;
+    }
+    for e in despawn_query.iter() {
+        commands.entity(e).despawn();
+    }
+    commands.run_system(battle_events.despawn_projectiles);
+
+    if let Ok((mut physics, mut v)) = player_query.single_mut() {
+        physics.velocity = Vec2::ZERO;
+        *v = Visibility::Visible;
+    }
+    player_stats.invincibility = 0.;
+    *game_over = GameOver {
+        timer: data.game.game_over.freeze_time,
+        ..Default::default()
+    };
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_game_over(
+    mut commands: Commands,
+    mut game_over: ResMut<GameOver>,
+    mut player_query: Query<
+        (
+            &PhysicsComponent,
+            &mut Sprite,
+            &mut SoulMode,
+            &mut Visibility,
+        ),
+        With<Player>,
+    >,
+    mut text_query: Query<(&mut Text2d, &mut TextColor), With<GameOverItem>>,
+    mut sounds: ResMut<SoundPlayer>,
+    mut player_stats: ResMut<PlayerStats>,
+    mut state: ResMut<NextState<AppState>>,
+    mut save_manager: ResMut<SaveManager>,
+    battle_events: Res<BattleEvents>,
+    render_layers: Res<RenderLayerStorage>,
+    asset_manager: Res<AssetManager>,
+    time: Res<Time<Fixed>>,
+    data: Res<Data>,
+) {
+    let settings = &data.game.game_over;
+    game_over.timer -= time.delta_secs();
+    match game_over.phase {
+        GameOverPhase::Freeze => {
+            if game_over.timer > 0. {
+                return;
+            }
+            //the soul breaks red whatever mode it was in
+            if let Ok((_, mut sprite, mut mode, _)) = player_query.single_mut() {
+                sprite.image = asset_manager.images[&settings.broken_sprite].clone();
+                sprite.color = Color::WHITE;
+                *mode = SoulMode::Red;
+            }
+            sounds.play_sound_once_local(asset_manager.sounds["heart_break"].clone());
+            game_over.phase = GameOverPhase::Break;
+            game_over.timer = settings.break_time;
+        }
+        GameOverPhase::Break => {
+            if game_over.timer > 0. {
+                return;
+            }
+            if let Ok((physics, _, _, mut v)) = player_query.single_mut() {
+                *v = Visibility::Hidden;
+                let mut rand = thread_rng();
+                for _ in 0..settings.shard_count {
+                    //shards burst upwards and fall off the screen
+                    let angle = rand.gen_range(0.0..PI);
+                    let speed = rand.gen_range(0.5..1.0) * settings.shard_speed;
+                    let velocity = Vec2::from_angle(angle) * speed;
+                    commands.spawn((
+                        Sprite {
+                            image: asset_manager.images[&settings.shard_sprite].clone(),
+                            custom_size: Some(Vec2::splat(SHARD_SIZE * 2.0)),
+                            ..Default::default()
+                        },
+                        Transform::from_translation(physics.position.extend(2.0)),
+                        PhysicsComponent {
+                            position: physics.position,
+                            ..Default::default()
+                        },
+                        Motion::new(vec![MotionSegment {
+                            kind: MotionKind::Accelerate {
+                                velocity: Some(velocity),
+                                acceleration: 0.,
+                                angular_velocity: 0.,
+                                gravity: Vec2::new(0., -settings.shard_gravity),
+                                drag: 0.,
+                                max_speed: 0.,
+                            },
+                            duration: 0.,
+                        }]),
+                        DespawnInTime::new(settings.shatter_time + settings.fade_time, None),
+                        OpacityFromTimer,
+                        render_layers.pre.clone(),
+                        Name::new("SoulShard"),
+                    ));
+                }
+            }
+            sounds.play_sound_once_local(asset_manager.sounds["heart_shatter"].clone());
+            game_over.phase = GameOverPhase::Shatter;
+            game_over.timer = settings.shatter_time;
+        }
+        GameOverPhase::Shatter => {
+            if game_over.timer > 0. {
+                return;
+            }
+            let title = commands
+                .spawn((
+                    Text2d::new("GAME OVER"),
+                    TextLayout::new(JustifyText::Center, LineBreak::NoWrap),
+                    TextFont {
+                        font: asset_manager.fonts["fonts/8-BIT WONDER.ttf"].clone(),
+                        font_size: 48.0,
+                        font_smoothing: bevy::text::FontSmoothing::None,
+                        ..Default::default()
+                    },
+                    TextColor(Color::WHITE.with_alpha(0.)),
+                    Transform::from_translation(Vec3::new(0., TITLE_Y, 3.0)),
+                    Name::new("GameOverTitle"),
+                    GameOverItem,
+                ))
+                .id();
+            game_over.title = Some(title);
+            sounds.play_sound_looped(asset_manager.sounds["game_over"].clone());
+            game_over.phase = GameOverPhase::Title;
+            game_over.timer = settings.fade_time;
+        }
+        GameOverPhase::Title => {
+            let alpha = 1.0 - (game_over.timer / settings.fade_time).clamp(0., 1.);
+            if let Some(title) = game_over.title
+                && let Ok((_, mut color)) = text_query.get_mut(title)
+            {
+                color.0.set_alpha(alpha);
+            }
+            if game_over.timer > 0. {
+                return;
+            }
+            if !settings.messages.is_empty() {
+                let i = thread_rng().gen_range(0..settings.messages.len());
+                game_over.message = settings.messages[i].clone();
+            }
+            let text = commands
+                .spawn((
+                    Text2d::new(""),
+                    TextLayout::new(JustifyText::Center, LineBreak::NoWrap),
+                    TextFont {
+                        font: asset_manager.fonts["fonts/DTM-Mono.ttf"].clone(),
+                        font_size: 26.0,
+                        font_smoothing: bevy::text::FontSmoothing::None,
+                        ..Default::default()
+                    },
+                    TextColor(Color::WHITE),
+                    Transform::from_translation(Vec3::new(0., MESSAGE_Y, 3.0)),
+                    Name::new("GameOverMessage"),
+                    GameOverItem,
+                ))
+                .id();
+            game_over.text = Some(text);
+            game_over.typed = 0;
+            game_over.phase = GameOverPhase::Message;
+            game_over.timer = settings.type_interval;
+        }
+        GameOverPhase::Message => {
+            if game_over.timer > 0. {
+                return;
+            }
+            game_over.timer += settings.type_interval;
+            let letters: Vec<char> = game_over.message.chars().collect();
+            if game_over.typed >= letters.len() {
+                game_over.phase = GameOverPhase::Wait;
+                return;
+            }
+            if !letters[game_over.typed].is_whitespace() {
+                sounds.play_sound_once_local(asset_manager.sounds["text"].clone());
+            }
+            game_over.typed += 1;
+            if let Some(text) = game_over.text
+                && let Ok((mut text, _)) = text_query.get_mut(text)
+            {
+                text.0 = letters[0..game_over.typed].iter().collect();
+            }
+        }
+        GameOverPhase::Wait => {}
+        GameOverPhase::Leave => {
+            let alpha = (game_over.timer / settings.fade_time).clamp(0., 1.);
+            for (_, mut color) in text_query.iter_mut() {
+                color.0.set_alpha(alpha);
+            }
+            if game_over.timer > 0. {
+                return;
+            }
+            game_over.phase = GameOverPhase::Done;
+            player_stats.health = player_stats.max_health;
+            //battles started from the overworld continue from the last save,
+            //or from where the battle started when there is none
+            if !data.game.overworld.map.is_empty() {
+                save_manager.load();
+                state.set(AppState::Overworld);
+                return;
+            }
+            commands.run_system(battle_events.events["restart_encounter"]);
+        }
+        GameOverPhase::Done => {}
+    }
+}
+
+fn continue_game_over(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut game_over: ResMut<GameOver>,
+    data: Res<Data>,
+) {
+    if game_over.phase == GameOverPhase::Wait && keys.just_pressed(KeyCode::KeyZ) {
+        game_over.phase = GameOverPhase::Leave;
+        game_over.timer = data.game.game_over.fade_time;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn clear_game_over(
+    mut commands: Commands,
+    despawn_query: Query<Entity, Or<(With<GameOverItem>, With<Song>)>>,
+    mut player_query: Query<(&mut Sprite, &mut Visibility), With<Player>>,
+    mut menu_query: Query<&mut Visibility, (With<MenuItem>, Without<Player>)>,
+    mut soul_switch: ResMut<SoulSwitch>,
+) {
+    for e in despawn_query.iter() {
+        commands.entity(e).despawn();
+    }
+    for mut v in menu_query.iter_mut() {
+        *v = Visibility::Visible;
+    }
+    //the soul comes back whole and red
+    if let Ok((mut sprite, mut v)) = player_query.single_mut() {
+        sprite.color = Color::WHITE;
+        *v = Visibility::Visible;
+    }
+    soul_switch.switch(SoulMode::Red);
+}
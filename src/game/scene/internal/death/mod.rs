@@ -1,2 +1,3 @@
 pub mod enemy_death;
+pub mod game_over;
 pub mod restart_screen;
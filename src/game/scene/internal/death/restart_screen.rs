@@ -1,9 +1,9 @@
 use bevy::{math::VectorSpace, prelude::*};
 
 use crate::game::{
-    data::data::Data, loading::loading::AssetManager, physics::physics_object::PhysicsComponent, player::player::{Player, PlayerStats}, scene::internal::{
-        bullet_board::{move_towards_vec, BulletBoard}, dodging::DodgingPhaseManager, helpers::{despawn::DespawnInMenu, menu_item::MenuItem}, menu::MenuState, menu_transition::MenuTransition, progress::Progress
-    }
+    data::data::Data, loading::loading::AssetManager, physics::physics_object::PhysicsComponent, player::{inventory::Inventory, player::{Player, PlayerStats}}, scene::{battle::BattleEvents, internal::{
+        bullet_board::{move_towards_vec, BulletBoard}, dodging::DodgingPhaseManager, encounter::Encounter, helpers::{despawn::DespawnInMenu, menu_item::MenuItem}, menu::MenuState, menu_transition::MenuTransition, progress::Progress, turn_script::TurnPlan
+    }}
 };
 
 pub struct RestartPlugin;
@@ -29,26 +29,42 @@ impl Plugin for RestartPlugin {
 }
 
 fn update_restart(
+    mut commands: Commands,
     keys: Res<ButtonInput<KeyCode>>,
+    player_query : Query<&PhysicsComponent, With<Player>>,
+    battle_events: Res<BattleEvents>,
+) {
+    if let Ok(physics) = player_query.single() {
+        if Vec2::length(physics.position) <= 2.0 && keys.just_pressed(KeyCode::KeyZ) {
+            commands.run_system(battle_events.events["restart_encounter"]);
+        }
+    }
+}
+
+//starts the encounter over from the selection menu with full health
+#[allow(clippy::too_many_arguments)]
+pub fn restart_encounter(
     mut menu_transition: ResMut<MenuTransition>,
     mut progress: ResMut<Progress>,
     mut player_stats: ResMut<PlayerStats>,
     mut bullet_board : ResMut<BulletBoard>,
     asset_manager : Res<AssetManager>,
     mut dodging_manager: ResMut<DodgingPhaseManager>,
-    mut player_query : Query<(&mut PhysicsComponent, &mut Player)>,
+    mut inventory: ResMut<Inventory>,
+    mut turn_plan: ResMut<TurnPlan>,
+    encounter: Res<Encounter>,
     data: Res<Data>,
 ) {
-    if let Ok((mut physics,mut p)) = player_query.single_mut() {
-        if Vec2::length(physics.position) <= 2.0 && keys.just_pressed(KeyCode::KeyZ) {
-            bullet_board.absolute_board(asset_manager.board_layouts["selection"].clone());
-            menu_transition.new_state(MenuState::Selection);
-            progress.turns = 0;
-            progress.health = data.game.opponent_data.health;
-            player_stats.health = player_stats.max_health;
-            dodging_manager.time = 0.;
-        }
-    }
+    bullet_board.absolute_board(asset_manager.board_layouts["selection"].clone());
+    menu_transition.new_state(MenuState::Selection);
+    progress.turns = 0;
+    encounter.reset_progress(&mut progress);
+    player_stats.health = player_stats.max_health;
+    inventory.items = data.game.player.inventory.clone();
+    inventory.weapon = data.game.player.weapon.clone();
+    inventory.armor = data.game.player.armor.clone();
+    *turn_plan = TurnPlan::default();
+    dodging_manager.time = 0.;
 }
 
 #[derive(Component)]
@@ -6,10 +6,11 @@ use crate::game::{
     data::data::Data,
     loading::loading::AssetManager,
     physics::physics_object::PhysicsComponent,
-    player::player::Player,
+    player::{inventory::Inventory, player::Player},
     scene::internal::{
         bullet_board::{self, BulletBoard},
         helpers::menu_item::MenuItem,
+        encounter::Encounter,
         menu::MenuState,
         menu_transition::MenuTransition,
         progress::Progress,
@@ -49,11 +50,45 @@ pub struct Decision {
     pub hover: Option<SystemId>,
 
     pub submenu: Option<DecisionMenu>,
+
+    //enemy the decision refers to, becomes the encounter target when picked
+    pub target: Option<usize>,
+    //inventory slot the decision refers to, becomes the selected item when picked
+    pub item: Option<usize>,
+    //ACT option the decision refers to, becomes the encounter act when picked
+    pub act: Option<usize>,
+    pub color: Color,
 }
 #[derive(Default, Clone)]
 pub struct DecisionMenu {
     pub left_column: Vec<Decision>,
     pub right_column: Vec<Decision>,
+
+    //when filled the menu is shown one page at a time instead of using its own columns
+    pub pages: Vec<DecisionMenu>,
+}
+
+//amount of decisions that fit on a page, two rows in each column
+pub const PAGE_SIZE: usize = 4;
+impl DecisionMenu {
+    pub fn paginate(decisions: Vec<Decision>) -> DecisionMenu {
+        let mut menu = DecisionMenu::default();
+        for chunk in decisions.chunks(PAGE_SIZE) {
+            let mut page = DecisionMenu::default();
+            for (i, decision) in chunk.iter().enumerate() {
+                if i % 2 == 0 {
+                    page.left_column.push(decision.clone());
+                } else {
+                    page.right_column.push(decision.clone());
+                }
+            }
+            menu.pages.push(page);
+        }
+        if menu.pages.len() == 1 {
+            return menu.pages.remove(0);
+        }
+        menu
+    }
 }
 #[derive(Default, Clone)]
 pub struct DecisionEntities {
@@ -67,6 +102,9 @@ pub struct Decisions {
     pub menu_entities: DecisionEntities,
     pub remove_decisions: Option<SystemId>,
 
+    pub pages: Vec<DecisionMenu>,
+    pub page: usize,
+
     pub switch_menu: bool,
     pub submenu: bool,
     pub selection: i32,
@@ -82,11 +120,30 @@ impl Decisions {
         self.menu_entities.left_column.clear();
         self.menu_entities.right_column.clear();
     }
-    fn enter_menu(&mut self, menu: DecisionMenu) {
+    fn enter_menu(&mut self, mut menu: DecisionMenu) {
+        self.pages = menu.pages.clone();
+        self.page = 0;
+        if !self.pages.is_empty() {
+            menu = self.pages[0].clone();
+        }
         self.decision_menu = Some(menu);
         self.switch_menu = true;
         self.reset_selections();
     }
+    fn turn_page(&mut self, i: i32) {
+        self.page = (self.page as i32 + i).rem_euclid(self.pages.len() as i32) as usize;
+        let menu = self.pages[self.page].clone();
+        let selection = self.selection;
+        self.decision_menu = Some(menu);
+        self.switch_menu = true;
+        self.reset_selections();
+        self.selection = selection;
+        //turning forwards lands on the left column, turning backwards on the right one
+        if i < 0 && !self.decision_menu.as_ref().unwrap().right_column.is_empty() {
+            self.side = 1;
+        }
+        self.vertical_cycle(0);
+    }
     pub fn spawn_decision(
         &mut self,
         mut commands: &mut Commands,
@@ -109,6 +166,7 @@ impl Decisions {
                 TextLayout::new(JustifyText::Left, LineBreak::WordBoundary),
                 Name::new("decision"),
                 text_font.clone(),
+                TextColor(decision.color),
                 Transform::from_translation((position).extend(1.0)),
                 DecisionMarker {},
                 MenuItem,
@@ -127,6 +185,20 @@ impl Decisions {
     }
     pub fn horizontal_cycle(&mut self, i: i32) {
         let decision_menu = self.decision_menu.as_ref().unwrap();
+        if i != 0 && self.pages.len() > 1 {
+            let mut last_side = 1;
+            if decision_menu.right_column.len() == 0 {
+                last_side = 0;
+            }
+            if i > 0 && self.side == last_side {
+                self.turn_page(1);
+                return;
+            }
+            if i < 0 && self.side == 0 {
+                self.turn_page(-1);
+                return;
+            }
+        }
         if decision_menu.right_column.len() == 0 {
             self.side = 0;
         } else {
@@ -158,6 +230,10 @@ impl Decision {
             system: Some(system),
             submenu: None,
             hover: None,
+            target: None,
+            item: None,
+            act: None,
+            color: Color::WHITE,
         };
     }
     pub fn new_with_hover(display: String, system: SystemId, hover: SystemId) -> Decision {
@@ -166,6 +242,10 @@ impl Decision {
             system: Some(system),
             submenu: None,
             hover: Some(hover),
+            target: None,
+            item: None,
+            act: None,
+            color: Color::WHITE,
         };
     }
     pub fn new_with_menu(display: String, submenu: Option<DecisionMenu>) -> Decision {
@@ -174,8 +254,28 @@ impl Decision {
             system: None,
             submenu: submenu,
             hover: None,
+            target: None,
+            item: None,
+            act: None,
+            color: Color::WHITE,
         };
     }
+    pub fn with_target(mut self, target: usize) -> Decision {
+        self.target = Some(target);
+        self
+    }
+    pub fn with_item(mut self, item: usize) -> Decision {
+        self.item = Some(item);
+        self
+    }
+    pub fn with_act(mut self, act: usize) -> Decision {
+        self.act = Some(act);
+        self
+    }
+    pub fn with_color(mut self, color: Color) -> Decision {
+        self.color = color;
+        self
+    }
 }
 
 pub fn remove_decisions(
@@ -186,7 +286,7 @@ pub fn remove_decisions(
         commands.entity(e).despawn();
     }
 }
-fn init_decision_menu(mut menu_select: ResMut<MenuSelect>, mut decisions: ResMut<Decisions>) {
+pub fn init_decision_menu(mut menu_select: ResMut<MenuSelect>, mut decisions: ResMut<Decisions>) {
     decisions.increment = 32.;
     decisions.spacing = 256.;
 
@@ -227,6 +327,8 @@ pub fn update_decisions(
     mut text_box: ResMut<TextBox>,
     data: Res<Data>,
     progress: Res<Progress>,
+    mut encounter: ResMut<Encounter>,
+    mut inventory: ResMut<Inventory>,
     mut sounds : ResMut<SoundPlayer>,
     asset_manager : Res<AssetManager>,
 ) {
@@ -238,6 +340,15 @@ pub fn update_decisions(
             commands.run_system(decision.0.hover.unwrap());
         }
         if keys.just_pressed(KeyCode::KeyZ) {
+            if let Some(target) = decision.0.target {
+                encounter.target = target;
+            }
+            if let Some(item) = decision.0.item {
+                inventory.selected = item;
+            }
+            if let Some(act) = decision.0.act {
+                encounter.act = act;
+            }
             if decision.0.submenu.is_some() {
                 decisions.enter_menu(decision.0.submenu.unwrap());
                 decisions.submenu = true;
@@ -322,6 +433,33 @@ fn update_decision_spawning(
             );
             decisions.menu_entities.right_column.push(e);
         }
+
+        if decisions.pages.len() > 1 {
+            let parent = commands
+                .spawn(Transform::from_translation(
+                    bullet_board.position.extend(0.0),
+                ))
+                .id();
+            let e = commands
+                .spawn((
+                    Text2d::new("PAGE ".to_string() + (decisions.page + 1).to_string().as_str()),
+                    TextBounds::from(Vec2::new(bullet_board.width, bullet_board.height)),
+                    TextLayout::new(JustifyText::Left, LineBreak::WordBoundary),
+                    Name::new("page"),
+                    text_font.clone(),
+                    Transform::from_translation(
+                        Vec2::new(
+                            14.1 + 49. + decisions.spacing,
+                            -16. - decisions.increment * 2.0,
+                        )
+                        .extend(1.0),
+                    ),
+                    DecisionMarker {},
+                    MenuItem,
+                ))
+                .id();
+            commands.entity(parent).add_child(e);
+        }
     }
 }
 
@@ -68,35 +68,14 @@ fn update_dodging_phase(
     }
 }
 
-fn constrain_player(
+pub fn constrain_player(
     mut bullet_board: ResMut<BulletBoard>,
     mut player_query: Query<(&mut Player, &mut PhysicsComponent)>,
     data: Res<Data>,
 ) {
+    //the soul is treated as a square so it can be pushed in along any edge
+    let margin = data.game.player.sprite_size_x.max(data.game.player.sprite_size_y) / 2.0;
     for (mut player, mut physics) in player_query.iter_mut() {
-        if physics.position.x + data.game.player.sprite_size_x / 2.0
-            > bullet_board.position.x + bullet_board.width / 2.0
-        {
-            physics.position.x = bullet_board.position.x + bullet_board.width / 2.0
-                - data.game.player.sprite_size_x / 2.0;
-        }
-        if physics.position.x - data.game.player.sprite_size_x / 2.0
-            < bullet_board.position.x - bullet_board.width / 2.0
-        {
-            physics.position.x = bullet_board.position.x - bullet_board.width / 2.0
-                + data.game.player.sprite_size_x / 2.0;
-        }
-        if physics.position.y - data.game.player.sprite_size_y / 2.0
-            < bullet_board.position.y - bullet_board.height / 2.0
-        {
-            physics.position.y = bullet_board.position.y - bullet_board.height / 2.0
-                + data.game.player.sprite_size_y / 2.0;
-        }
-        if physics.position.y + data.game.player.sprite_size_y / 2.0
-            > bullet_board.position.y + bullet_board.height / 2.0
-        {
-            physics.position.y = bullet_board.position.y + bullet_board.height / 2.0
-                - data.game.player.sprite_size_y / 2.0;
-        }
+        physics.position = bullet_board.constrain(physics.position, margin);
     }
 }
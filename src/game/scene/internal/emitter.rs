@@ -0,0 +1,265 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::{Rng, thread_rng};
+
+use crate::game::{
+    data::data::{EmitterData, EmitterPattern},
+    loading::loading::AssetManager,
+    physics::{
+        motion::{Motion, MotionKind, MotionSegment},
+        physics_object::PhysicsComponent,
+    },
+    player::player::Player,
+    scene::internal::{
+        bullet_board::BulletBoard,
+        health::{Damage, damage_color},
+        helpers::despawn::{DespawnInMenu, DespawnInTime},
+        projectile::BulletPool,
+    },
+    state::state::AppState,
+};
+
+pub struct EmitterPlugin;
+impl Plugin for EmitterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            update_emitters.run_if(in_state(AppState::Level)),
+        );
+    }
+}
+
+//what an emitter stays attached to
+#[derive(Clone, Copy, PartialEq)]
+pub enum EmitterFollow {
+    None,
+    //offset from the board center, turning with the board
+    Board,
+    //offset from another entity's physics, the emitter is removed with it
+    Entity(Entity),
+}
+
+//shoots bullets from its PhysicsComponent's position on a schedule
+#[derive(Component)]
+pub struct Emitter {
+    pub data: EmitterData,
+    pub follow: EmitterFollow,
+    pub offset: Vec2,
+    //time until the next shot
+    pub timer: f32,
+    pub shots: i32,
+    //radians, turned by the angle step after every shot
+    pub angle: f32,
+}
+
+//`offset` is relative to whatever the emitter follows, or the position
+//it starts at when it follows nothing
+pub fn spawn_emitter(
+    commands: &mut Commands,
+    data: &EmitterData,
+    offset: Vec2,
+    follow: EmitterFollow,
+) -> Entity {
+    commands
+        .spawn((
+            PhysicsComponent::new_full(
+                offset,
+                Vec2::new(data.velocity_x, data.velocity_y),
+                Vec2::ZERO,
+                Vec2::ZERO,
+            ),
+            Emitter {
+                data: data.clone(),
+                follow,
+                offset,
+                timer: data.delay,
+                shots: 0,
+                angle: data.angle.to_radians(),
+            },
+            DespawnInMenu,
+        ))
+        .id()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_emitters(
+    mut commands: Commands,
+    mut emitter_query: Query<(Entity, &mut Emitter, &mut PhysicsComponent)>,
+    mut pool: ResMut<BulletPool>,
+    target_query: Query<&PhysicsComponent, Without<Emitter>>,
+    player_query: Query<&PhysicsComponent, (With<Player>, Without<Emitter>)>,
+    bullet_board: Res<BulletBoard>,
+    asset_manager: Res<AssetManager>,
+    time: Res<Time<Fixed>>,
+) {
+    for (e, mut emitter, mut physics) in emitter_query.iter_mut() {
+        match emitter.follow {
+            EmitterFollow::None => {}
+            EmitterFollow::Board => {
+                physics.position = bullet_board.position
+                    + Vec2::from_angle(bullet_board.rotation).rotate(emitter.offset);
+            }
+            EmitterFollow::Entity(target) => {
+                let Ok(target) = target_query.get(target) else {
+                    commands.entity(e).despawn();
+                    continue;
+                };
+                physics.position = target.position + emitter.offset;
+            }
+        }
+
+        emitter.timer -= time.delta_secs();
+        if emitter.timer > 0. {
+            continue;
+        }
+        emitter.timer += emitter.data.interval;
+        emitter.shots += 1;
+
+        let mut angle = emitter.angle;
+        if emitter.data.pattern == EmitterPattern::Aimed
+            && let Ok(player) = player_query.single()
+        {
+            angle += (player.position - physics.position).to_angle();
+        }
+        let shots = shot_positions(&emitter.data, physics.position, angle, &bullet_board);
+        for (position, direction) in shots {
+            spawn_emitted_bullet(
+                &mut commands,
+                &mut pool,
+                &asset_manager,
+                &emitter.data,
+                position,
+                direction,
+            );
+        }
+
+        emitter.angle += emitter.data.angle_step.to_radians();
+        if emitter.data.shots > 0 && emitter.shots >= emitter.data.shots {
+            commands.entity(e).despawn();
+        }
+    }
+}
+
+//where each bullet of a shot starts and the angle it moves at
+fn shot_positions(
+    data: &EmitterData,
+    position: Vec2,
+    angle: f32,
+    bullet_board: &BulletBoard,
+) -> Vec<(Vec2, f32)> {
+    let count = data.count.max(1);
+    let mut shots = Vec::new();
+    match data.pattern {
+        EmitterPattern::Ring | EmitterPattern::Spiral => {
+            for i in 0..count {
+                shots.push((position, angle + TAU * i as f32 / count as f32));
+            }
+        }
+        EmitterPattern::Spread | EmitterPattern::Aimed => {
+            let spread = data.spread.to_radians();
+            for i in 0..count {
+                let mut offset = 0.;
+                if count > 1 {
+                    offset = spread * (i as f32 / (count - 1) as f32 - 0.5);
+                }
+                shots.push((position, angle + offset));
+            }
+        }
+        EmitterPattern::Line => {
+            let side = Vec2::from_angle(angle).perp();
+            for i in 0..count {
+                let offset = data.spacing * (i as f32 - (count - 1) as f32 / 2.0);
+                shots.push((position + side * offset, angle));
+            }
+        }
+        EmitterPattern::RandomEdge => {
+            let mut rand = thread_rng();
+            let half_size = Vec2::new(bullet_board.width, bullet_board.height) / 2.0
+                + Vec2::new(data.half_hitbox_x, data.half_hitbox_y);
+            let turn = Vec2::from_angle(bullet_board.rotation);
+            for _ in 0..count {
+                //the edges are picked by length so the bullets are spread evenly
+                let along = rand.gen_range(-1.0..=1.0);
+                let mut normal = Vec2::new(1., 0.);
+                let mut point = Vec2::new(half_size.x, along * half_size.y);
+                if rand.gen_bool((half_size.x / (half_size.x + half_size.y)) as f64) {
+                    normal = Vec2::new(0., 1.);
+                    point = Vec2::new(along * half_size.x, half_size.y);
+                }
+                if rand.gen_bool(0.5) {
+                    normal = -normal;
+                    point = -point;
+                }
+                let start = bullet_board.position + turn.rotate(point);
+                shots.push((start, turn.rotate(-normal).to_angle()));
+            }
+        }
+    }
+    shots
+}
+
+pub fn spawn_emitted_bullet(
+    commands: &mut Commands,
+    pool: &mut BulletPool,
+    asset_manager: &AssetManager,
+    data: &EmitterData,
+    position: Vec2,
+    angle: f32,
+) {
+    //unknown sprites are reported when the attacks are loaded
+    let Some(image) = asset_manager.images.get(&data.sprite) else {
+        return;
+    };
+    let half_hitbox = Vec2::new(data.half_hitbox_x, data.half_hitbox_y);
+    let mut rotation = 0.;
+    if data.face_velocity {
+        rotation = angle;
+    }
+    let bullet = pool.spawn(
+        commands,
+        (
+            Sprite {
+                image: image.clone(),
+                color: damage_color(data.kind),
+                ..Default::default()
+            },
+            Transform::from_translation(position.extend(-1.0)),
+            PhysicsComponent {
+                rotation,
+                shape: data.shape,
+                ..PhysicsComponent::new_full(
+                    position,
+                    Vec2::from_angle(angle) * data.speed,
+                    half_hitbox,
+                    half_hitbox,
+                )
+            },
+            Motion {
+                face_velocity: data.face_velocity,
+                heading: angle,
+                ..Motion::new(vec![MotionSegment {
+                    kind: MotionKind::Accelerate {
+                        velocity: None,
+                        acceleration: data.acceleration,
+                        angular_velocity: data.angular_velocity.to_radians(),
+                        gravity: Vec2::ZERO,
+                        drag: 0.,
+                        max_speed: data.max_speed,
+                    },
+                    duration: 0.,
+                }])
+            },
+            Damage {
+                damage: data.damage,
+                kind: data.kind,
+            },
+            DespawnInMenu,
+        ),
+    );
+    if data.lifetime > 0. {
+        commands
+            .entity(bullet)
+            .insert(DespawnInTime::new(data.lifetime, None));
+    }
+}
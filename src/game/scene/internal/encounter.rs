@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+use crate::game::{
+    data::data::{Data, EncounterData, OpponentData},
+    loading::loading::AssetManager,
+    scene::internal::progress::{EnemyProgress, Progress},
+};
+
+pub struct EncounterPlugin;
+impl Plugin for EncounterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Encounter>();
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Encounter {
+    pub data: EncounterData,
+    //the enemy picked in the FIGHT or ACT menu
+    pub target: usize,
+    //the ACT option picked for the target
+    pub act: usize,
+}
+
+impl Encounter {
+    pub fn get_enemy(&self, index: usize) -> &OpponentData {
+        &self.data.enemies[index]
+    }
+    pub fn get_target(&self) -> &OpponentData {
+        self.get_enemy(self.target)
+    }
+    //horizontal position of an enemy, the group is centered on the screen
+    pub fn get_offset(&self, index: usize) -> f32 {
+        let count = self.data.enemies.len() as f32;
+        (index as f32 - (count - 1.0) / 2.0) * self.data.spacing
+    }
+    pub fn find_enemy(&self, name: &String) -> Option<usize> {
+        self.data.enemies.iter().position(|e| e.name == *name)
+    }
+    pub fn spareable(&self, index: usize, progress: &Progress) -> bool {
+        let enemy = self.get_enemy(index);
+        let enemy_progress = &progress.enemies[index];
+        if enemy.spare_mercy > 0 && enemy_progress.mercy >= enemy.spare_mercy {
+            return true;
+        }
+        if enemy.spare_turns > 0 && progress.turns >= enemy.spare_turns {
+            return true;
+        }
+        false
+    }
+    //loads an encounter and resets the battle's progress for it
+    pub fn begin(&mut self, data: EncounterData, progress: &mut Progress, start_turn: i32) {
+        self.data = data;
+        self.target = 0;
+        self.act = 0;
+        self.reset_progress(progress);
+        progress.turns = start_turn;
+    }
+    pub fn reset_progress(&self, progress: &mut Progress) {
+        progress.enemies = self
+            .data
+            .enemies
+            .iter()
+            .map(|e| EnemyProgress {
+                health: e.health,
+                killed: false,
+                spared: false,
+                mercy: 0,
+                at: e.at,
+                df: e.df,
+            })
+            .collect();
+        progress.flags.clear();
+        progress.items_used.clear();
+    }
+}
+
+pub fn start_encounter(
+    mut encounter: ResMut<Encounter>,
+    mut progress: ResMut<Progress>,
+    asset_manager: Res<AssetManager>,
+    data: Res<Data>,
+) {
+    encounter.begin(
+        asset_manager.encounters[&data.game.battle.encounter].clone(),
+        &mut progress,
+        data.game.player.start_turn,
+    );
+}
@@ -6,6 +6,7 @@ use crate::game::{
     scene::internal::{
         bullet_board::BulletBoard,
         decisions::Decisions,
+        encounter::Encounter,
         helpers::menu_item::MenuItem,
         progress::Progress,
         stats::{HealthBar, HealthBarType},
@@ -38,16 +39,23 @@ pub fn manage_enemy_healthbar(
     data: Res<Data>,
     b_board: Res<BulletBoard>,
     progress: Res<Progress>,
+    encounter: Res<Encounter>,
 ) {
+    let (decision, entity) = decisions.get_decision();
+    if decision.target.is_none() {
+        return;
+    }
+    let target = decision.target.unwrap();
     for (mut h, mut h_t, mut e, mut v) in enemy_healthbar.iter_mut() {
-        if let Ok(mut t) = text_query.get_mut(decisions.menu_entities.left_column[0]) {
-            h.health = progress.health;
-            h.max_health = data.game.opponent_data.health;
+        if let Ok(mut t) = text_query.get_mut(entity) {
+            h.health = progress.enemies[target].health;
+            h.max_health = encounter.get_enemy(target).health;
 
             let pos = Vec2::new(
                 b_board.position.x - b_board.width / 2.0 + 65.0 + t.size.x + 62.0,
                 -data.game.player.sprite_size_y / 2.0 + b_board.position.y + b_board.height / 2.0
-                    - 23.0,
+                    - 23.0
+                    - decisions.increment * decisions.selection as f32,
             );
             h.position = pos;
             *v = Visibility::Visible;
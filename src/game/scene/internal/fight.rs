@@ -2,11 +2,13 @@ use bevy::prelude::*;
 use rand::{Rng, thread_rng};
 
 use crate::game::{
-    animation::animation::Animator, data::data::Data, loading::loading::AssetManager, physics::physics_object::PhysicsComponent, player::player::Player, scene::{
+    animation::animation::Animator, data::data::Data, loading::loading::AssetManager, physics::physics_object::PhysicsComponent, player::{inventory::Inventory, player::{Player, PlayerStats}}, scene::{
         battle::BattleEvents,
         internal::{
             bullet_board::{self, BulletBoard},
             decisions::Decisions,
+            encounter::Encounter,
+            fight_minigame::{FightMinigame, TimingBars, new_minigame},
             helpers::menu_item::MenuItem,
             menu::MenuState,
             menu_transition::MenuTransition,
@@ -22,6 +24,7 @@ impl Plugin for FightPlugin {
         app.init_resource::<FightManager>()
             .add_systems(OnEnter(AppState::Level), (spawn_fight_bar, spawn_slash))
             .add_systems(OnEnter(MenuState::Fight), init_fight)
+            .add_systems(OnExit(MenuState::Fight), despawn_timing_bars)
             .add_systems(
                 FixedUpdate,
                 (update_fight_logic, update_slash_position).run_if(in_state(AppState::Level)),
@@ -46,11 +49,16 @@ pub struct FightManager {
     pub attack_animation: f32,
     pub fade_timer: f32,
     pub strike: bool,
-    pub position: f32,
+    pub timing: TimingBars,
+    //game played with the bars, taken from the weapon when the fight starts
+    pub minigame: Option<Box<dyn FightMinigame>>,
     pub exit_fight_menu: bool,
     pub trigger_damage: bool,
     pub miss: bool,
 
+    //animation played over the enemy, taken from the weapon
+    pub slash: String,
+
     pub death_animation: f32,
 }
 impl FightManager {
@@ -60,19 +68,22 @@ impl FightManager {
         }
         self.strike = true;
     }
+    //strikes once every bar was stopped or went past
+    pub fn finish(&mut self) {
+        self.miss = self.timing.missed();
+        if self.miss {
+            log::info!("miss");
+        }
+        self.trigger_damage();
+    }
     pub fn calculate_damage(&mut self, mut atk: f32, def: f32) -> i32 {
         atk = atk + 10.;
-        let distance_from_center = self.position.abs();
-        let target_width = 565.0 / 2.0;
         let mut rand = thread_rng();
-        if distance_from_center <= 12. {
-            return ((atk - def + rand.gen_range((1.)..(2.))) * 2.2).round() as i32;
-        } else {
-            return ((atk - def + rand.gen_range((1.)..(2.)))
-                * (1. - distance_from_center / target_width)
-                * 2.)
-                .round() as i32;
-        }
+        let multiplier = match &self.minigame {
+            Some(minigame) => minigame.multiplier(&self.timing),
+            None => self.timing.average_score(),
+        };
+        return ((atk - def + rand.gen_range((1.)..(2.))) * multiplier).round() as i32;
     }
 }
 
@@ -80,7 +91,9 @@ impl FightManager {
 pub struct FightBar;
 
 #[derive(Component)]
-pub struct TimingBar;
+pub struct TimingBar {
+    pub index: usize,
+}
 
 fn update_player_visibility(mut player_query: Query<(&mut Visibility), With<Player>>) {
     if let Ok(mut v) = player_query.single_mut() {
@@ -92,28 +105,34 @@ fn update_fight_logic(
     menu_state: Res<State<MenuState>>,
     mut fight: ResMut<FightManager>,
     mut fightbar_query: Query<(&mut Sprite, &mut Transform), With<FightBar>>,
-    mut timing_query: Query<(&mut Visibility), With<TimingBar>>,
     mut menu_transition: ResMut<MenuTransition>,
     mut battle: ResMut<BattleEvents>,
     mut progress: ResMut<Progress>,
     mut opponent_bar_manager: ResMut<OpponentHealthBarManager>,
+    player_stats: Res<PlayerStats>,
+    inventory: Res<Inventory>,
+    encounter: Res<Encounter>,
+    asset_manager: Res<AssetManager>,
     data: Res<Data>,
     time: Res<Time<Fixed>>,
 ) {
+    let target = encounter.target;
     if fight.strike {
         if fight.trigger_damage {
             if !fight.miss {
+                let at = player_stats.at + inventory.bonus_at(&asset_manager.items);
                 let damage = fight.calculate_damage(
-                    data.game.player.at as f32,
-                    data.game.opponent_data.df as f32,
+                    at as f32,
+                    progress.enemies[target].df as f32,
                 );
+                let enemy = &mut progress.enemies[target];
                 opponent_bar_manager.damage_display = damage;
-                opponent_bar_manager.old_health = progress.health;
-                progress.health -= damage;
-                if progress.health < 0 {
-                    progress.health = 0;
+                opponent_bar_manager.old_health = enemy.health;
+                enemy.health -= damage;
+                if enemy.health < 0 {
+                    enemy.health = 0;
                 }
-                opponent_bar_manager.new_health = progress.health;
+                opponent_bar_manager.new_health = enemy.health;
             }
 
             fight.trigger_damage = false;
@@ -125,7 +144,7 @@ fn update_fight_logic(
             if fight.fade_timer <= 0. {
                 fight.strike = false;
             }
-            if progress.health <= 0 {
+            if progress.enemies[target].health <= 0 {
                 menu_transition.new_state(MenuState::EnemyDeath);
             }
             else {
@@ -142,13 +161,6 @@ fn update_fight_logic(
         s.color.set_alpha(ratio);
         t.scale.x = ratio;
     }
-    if let Ok((mut v)) = timing_query.single_mut() {
-        if fight.attack_animation > 0. {
-            *v = Visibility::Visible;
-        } else {
-            *v = Visibility::Hidden;
-        }
-    }
 }
 fn spawn_fight_bar(
     mut commands: Commands,
@@ -165,27 +177,6 @@ fn spawn_fight_bar(
         Name::new("FightBar"),
         MenuItem,
     ));
-
-    commands.spawn((
-        Sprite {
-            image: asset_manager.images["sprites/timing.png"].clone(),
-            texture_atlas: Some(TextureAtlas {
-                layout: asset_manager.atlases["timing"].clone(),
-                index: 0,
-                ..default()
-            }),
-            ..Default::default()
-        },
-        Animator {
-            current_animation: "idle".to_string(),
-            animation_bank: asset_manager.animations["timing"].clone(),
-            ..Default::default()
-        },
-        Transform::from_translation(bullet_board.position.extend(1.0)),
-        TimingBar,
-        MenuItem,
-        Visibility::Hidden,
-    ));
 }
 #[derive(Component)]
 pub struct Slash;
@@ -215,18 +206,23 @@ fn update_slash_position(
     mut fight: ResMut<FightManager>,
     mut slash_query: Query<(&mut Transform, &mut Slash, &mut Animator)>,
     mut opponent_query: Query<(&mut PhysicsComponent, &mut Opponent)>,
+    encounter: Res<Encounter>,
     data: Res<Data>,
 ) {
     if let Ok((mut transform, mut slash, mut animator)) = slash_query.single_mut() {
-        if let Ok((mut physics, mut opponent)) = opponent_query.single_mut() {
-            transform.translation.x = (physics.position.x).round();
-            transform.translation.y =
-                (physics.position.y - data.game.opponent_data.height * 2.0 / 2.0 + 94.0 / 2.0).round();
+        for (mut physics, mut opponent) in opponent_query.iter_mut() {
+            if opponent.index == encounter.target {
+                transform.translation.x = (physics.position.x).round();
+                transform.translation.y = (physics.position.y
+                    - encounter.get_target().height * 2.0 / 2.0
+                    + 94.0 / 2.0)
+                    .round();
+            }
         }
         animator.current_animation = "idle".to_string();
         if fight.strike && !fight.miss {
             if fight.attack_animation >= 1.0 {
-                animator.current_animation = "slash".to_string();
+                animator.current_animation = fight.slash.clone();
             }
         }
     }
@@ -234,48 +230,117 @@ fn update_slash_position(
 fn init_fight(
     mut commands: Commands,
     mut fight: ResMut<FightManager>,
-    mut timing_query: Query<(&mut TimingBar, &mut Transform)>,
     mut decisions: ResMut<Decisions>,
+    inventory: Res<Inventory>,
     bullet_board: Res<BulletBoard>,
+    images: Res<Assets<Image>>,
+    asset_manager: Res<AssetManager>,
     data: Res<Data>,
 ) {
-    if let Ok((mut bar, mut t)) = timing_query.single_mut() {
-        fight.position = -bullet_board.border - bullet_board.width / 2.0;
-        fight.fade_timer = data.game.fight_bar.fade_time;
-        fight.attack_animation = data.game.fight_bar.attack_animation;
-        fight.exit_fight_menu = false;
-        fight.strike = false;
-        commands.run_system(decisions.remove_decisions.unwrap());
+    let weapon = inventory
+        .weapon(&asset_manager.items)
+        .map(|w| w.fight.clone())
+        .unwrap_or_default();
+    //the bars travel across the whole fight bar sprite
+    let mut half_width = bullet_board.border + bullet_board.width / 2.0;
+    if let Some(image) = images.get(&asset_manager.images["sprites/fightbar.png"]) {
+        half_width = image.width() as f32 / 2.0;
+    }
+    let mut speed = data.game.player.attack_speed;
+    if weapon.attack_speed > 0. {
+        speed = weapon.attack_speed;
     }
+    fight.timing = TimingBars {
+        speed,
+        half_width,
+        crit_width: weapon.crit_width,
+        ..Default::default()
+    };
+    let mut minigame = new_minigame(weapon.minigame);
+    minigame.start(&mut fight.timing, &weapon);
+    fight.minigame = Some(minigame);
+
+    for i in 0..fight.timing.bars.len() {
+        commands.spawn((
+            Sprite {
+                image: asset_manager.images["sprites/timing.png"].clone(),
+                texture_atlas: Some(TextureAtlas {
+                    layout: asset_manager.atlases["timing"].clone(),
+                    index: 0,
+                }),
+                ..Default::default()
+            },
+            Animator {
+                current_animation: "idle".to_string(),
+                animation_bank: asset_manager.animations["timing"].clone(),
+                ..Default::default()
+            },
+            Transform::from_translation(bullet_board.position.extend(1.0)),
+            TimingBar { index: i },
+            MenuItem,
+            Visibility::Hidden,
+        ));
+    }
+    fight.slash = weapon.slash;
+
+    fight.fade_timer = data.game.fight_bar.fade_time;
+    fight.attack_animation = data.game.fight_bar.attack_animation;
+    fight.exit_fight_menu = false;
+    fight.strike = false;
+    fight.miss = false;
+    commands.run_system(decisions.remove_decisions.unwrap());
 }
 fn update_fight_bar(
-    mut timing_query: Query<(&mut TimingBar, &mut Animator, &mut Transform)>,
+    mut timing_query: Query<(&TimingBar, &mut Animator, &mut Transform, &mut Visibility)>,
     mut fight: ResMut<FightManager>,
     bullet_board: Res<BulletBoard>,
-    data: Res<Data>,
 ) {
-    if let Ok((mut bar, mut a, mut t)) = timing_query.single_mut() {
-        if !fight.strike {
-            fight.position += data.game.player.attack_speed;
-            if fight.position >= bullet_board.width / 2.0 + bullet_board.border {
-                fight.trigger_damage();
-                //fight.attack_animation = 0.;
-                fight.miss = true;
-                log::info!("miss");
-            }
-            a.current_animation = "idle".to_string();
-        } else {
+    let fight = &mut *fight;
+    if !fight.strike {
+        //stopped bars stay where they were hit
+        if let Some(minigame) = &mut fight.minigame {
+            minigame.advance(&mut fight.timing);
+        }
+        if fight.timing.done() {
+            fight.finish();
+        }
+    }
+    let end = fight.timing.half_width;
+    for (bar, mut a, mut t, mut v) in timing_query.iter_mut() {
+        let Some(moving) = fight.timing.bars.get(bar.index) else {
+            continue;
+        };
+        a.current_animation = "idle".to_string();
+        if fight.strike || moving.stopped {
             a.current_animation = "flash".to_string();
         }
-        t.translation.x = fight.position.floor();
-    
+        t.translation.x = (bullet_board.position.x + moving.position).floor();
+        //bars waiting their turn are hidden until they reach the fight bar
+        *v = Visibility::Hidden;
+        if fight.attack_animation > 0. && moving.position.abs() <= end {
+            *v = Visibility::Visible;
+        }
+    }
+}
+
+fn despawn_timing_bars(mut commands: Commands, timing_query: Query<Entity, With<TimingBar>>) {
+    for e in timing_query.iter() {
+        commands.entity(e).despawn();
     }
 }
 
 fn update_fight_controls(asset_manager: Res<AssetManager>,keys: Res<ButtonInput<KeyCode>>, mut fight: ResMut<FightManager>,mut sounds : ResMut<SoundPlayer>,) {
-    if keys.just_pressed(KeyCode::KeyZ) {
-        fight.trigger_damage();
+    if !keys.just_pressed(KeyCode::KeyZ) || fight.strike {
+        return;
+    }
+    let fight = &mut *fight;
+    let Some(minigame) = &mut fight.minigame else {
+        return;
+    };
+    if minigame.press(&mut fight.timing) {
         sounds.play_sound_once_local(asset_manager.sounds["attack"].clone());
-        fight.miss = false;
+    }
+    if fight.timing.done() {
+        fight.finish();
     }
 }
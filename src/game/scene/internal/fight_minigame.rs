@@ -0,0 +1,335 @@
+use crate::game::data::data::{FightMinigameKind, WeaponFightData};
+
+//multiplier for stopping a bar inside the critical zone
+const CRIT_MULTIPLIER: f32 = 2.2;
+//bonus on top of the combined multiplier when every bar was a critical hit
+const PERFECT_BONUS: f32 = 0.2;
+
+//a timing bar moving over the fight bar
+#[derive(Clone, Default)]
+pub struct MovingBar {
+    //measured from the center of the fight bar
+    pub position: f32,
+    //1 moves right, -1 moves left
+    pub direction: f32,
+    pub stopped: bool,
+    //distance from the center it was stopped at, None when it went past the end
+    pub hit: Option<f32>,
+}
+
+//the bars of one FIGHT, shared by every minigame
+#[derive(Default)]
+pub struct TimingBars {
+    pub bars: Vec<MovingBar>,
+    //pixels each frame
+    pub speed: f32,
+    //half the width of the fight bar sprite, bars past it are missed
+    pub half_width: f32,
+    //distance from the center that counts as a critical hit
+    pub crit_width: f32,
+}
+
+impl TimingBars {
+    pub fn done(&self) -> bool {
+        self.bars.iter().all(|b| b.stopped)
+    }
+    pub fn missed(&self) -> bool {
+        self.bars.iter().all(|b| b.hit.is_none())
+    }
+    //first bar that is still moving
+    pub fn next(&self) -> Option<usize> {
+        self.bars.iter().position(|b| !b.stopped)
+    }
+    //moving bar nearest to the center that already reached the fight bar
+    pub fn closest(&self) -> Option<usize> {
+        let mut closest = None;
+        let mut distance = f32::MAX;
+        for i in 0..self.bars.len() {
+            let bar = &self.bars[i];
+            if bar.stopped || bar.position.abs() > self.half_width {
+                continue;
+            }
+            if bar.position.abs() < distance {
+                distance = bar.position.abs();
+                closest = Some(i);
+            }
+        }
+        closest
+    }
+    pub fn stop(&mut self, index: usize) {
+        let bar = &mut self.bars[index];
+        bar.stopped = true;
+        bar.hit = Some(bar.position.abs());
+    }
+    //moves every bar that was not stopped, bars that leave the far end are missed
+    pub fn advance(&mut self) {
+        for i in 0..self.bars.len() {
+            let bar = &mut self.bars[i];
+            if bar.stopped {
+                continue;
+            }
+            bar.position += self.speed * bar.direction;
+            if bar.position * bar.direction >= self.half_width {
+                bar.stopped = true;
+                bar.hit = None;
+            }
+        }
+    }
+    //multiplier for one bar, the closer to the center the higher
+    pub fn score(&self, distance: f32) -> f32 {
+        if distance <= self.crit_width {
+            return CRIT_MULTIPLIER;
+        }
+        (1. - distance / self.half_width).max(0.) * 2.
+    }
+    //the bars share the hit, a missed bar adds nothing
+    pub fn average_score(&self) -> f32 {
+        let mut multiplier = 0.;
+        for i in 0..self.bars.len() {
+            if let Some(distance) = self.bars[i].hit {
+                multiplier += self.score(distance);
+            }
+        }
+        multiplier / self.bars.len().max(1) as f32
+    }
+    fn push(&mut self, position: f32, direction: f32) {
+        self.bars.push(MovingBar {
+            position,
+            direction,
+            ..Default::default()
+        });
+    }
+}
+
+//game played on the fight bar when attacking, picked by the weapon
+pub trait FightMinigame: Send + Sync {
+    //places the bars when the fight starts
+    fn start(&mut self, bars: &mut TimingBars, weapon: &WeaponFightData);
+    //runs every frame until all bars are stopped
+    fn advance(&mut self, bars: &mut TimingBars) {
+        bars.advance();
+    }
+    //Z was pressed, returns true when it stopped a bar
+    fn press(&mut self, bars: &mut TimingBars) -> bool;
+    //damage multiplier once all bars are stopped
+    fn multiplier(&self, bars: &TimingBars) -> f32 {
+        bars.average_score()
+    }
+}
+
+pub fn new_minigame(kind: FightMinigameKind) -> Box<dyn FightMinigame> {
+    match kind {
+        FightMinigameKind::Single => Box::new(SingleBar),
+        FightMinigameKind::Staggered => Box::new(StaggeredBars),
+        FightMinigameKind::Reversed => Box::new(ReversedBar),
+        FightMinigameKind::Rhythm => Box::new(RhythmBars),
+    }
+}
+
+//one bar crossing from left to right
+pub struct SingleBar;
+impl FightMinigame for SingleBar {
+    fn start(&mut self, bars: &mut TimingBars, _weapon: &WeaponFightData) {
+        let start = -bars.half_width;
+        bars.push(start, 1.);
+    }
+    fn press(&mut self, bars: &mut TimingBars) -> bool {
+        let Some(i) = bars.next() else {
+            return false;
+        };
+        bars.stop(i);
+        true
+    }
+}
+
+//bars lined up to the left that come in one after another and are stopped in order
+pub struct StaggeredBars;
+impl FightMinigame for StaggeredBars {
+    fn start(&mut self, bars: &mut TimingBars, weapon: &WeaponFightData) {
+        let start = -bars.half_width;
+        for i in 0..weapon.bars.max(1) {
+            bars.push(start - i as f32 * weapon.bar_spacing, 1.);
+        }
+    }
+    fn press(&mut self, bars: &mut TimingBars) -> bool {
+        let Some(i) = bars.next() else {
+            return false;
+        };
+        //presses do nothing until the next bar reaches the fight bar
+        if bars.bars[i].position < -bars.half_width {
+            return false;
+        }
+        bars.stop(i);
+        true
+    }
+}
+
+//one bar crossing from right to left
+pub struct ReversedBar;
+impl FightMinigame for ReversedBar {
+    fn start(&mut self, bars: &mut TimingBars, _weapon: &WeaponFightData) {
+        let start = bars.half_width;
+        bars.push(start, -1.);
+    }
+    fn press(&mut self, bars: &mut TimingBars) -> bool {
+        let Some(i) = bars.next() else {
+            return false;
+        };
+        bars.stop(i);
+        true
+    }
+}
+
+//bars come from both sides in turn, each press stops the one nearest the center
+//and hitting every beat on the crit gives a bonus
+pub struct RhythmBars;
+impl FightMinigame for RhythmBars {
+    fn start(&mut self, bars: &mut TimingBars, weapon: &WeaponFightData) {
+        for i in 0..weapon.bars.max(1) {
+            let mut direction = 1.;
+            if i % 2 == 1 {
+                direction = -1.;
+            }
+            let offset = bars.half_width + i as f32 * weapon.bar_spacing;
+            bars.push(-offset * direction, direction);
+        }
+    }
+    fn press(&mut self, bars: &mut TimingBars) -> bool {
+        let Some(i) = bars.closest() else {
+            return false;
+        };
+        bars.stop(i);
+        true
+    }
+    fn multiplier(&self, bars: &TimingBars) -> f32 {
+        let mut multiplier = bars.average_score();
+        let perfect = bars
+            .bars
+            .iter()
+            .all(|b| b.hit.is_some_and(|d| d <= bars.crit_width));
+        if perfect {
+            multiplier += PERFECT_BONUS;
+        }
+        multiplier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bars() -> TimingBars {
+        TimingBars {
+            bars: Vec::new(),
+            speed: 10.,
+            half_width: 100.,
+            crit_width: 12.,
+        }
+    }
+
+    fn weapon(bars: i32) -> WeaponFightData {
+        WeaponFightData {
+            bars,
+            ..Default::default()
+        }
+    }
+
+    fn advance(minigame: &mut Box<dyn FightMinigame>, bars: &mut TimingBars, frames: i32) {
+        for _ in 0..frames {
+            minigame.advance(bars);
+        }
+    }
+
+    fn assert_near(value: f32, expected: f32) {
+        assert!((value - expected).abs() < 0.0001, "{value} != {expected}");
+    }
+
+    #[test]
+    fn score_is_critical_near_the_center_and_falls_off_to_the_edge() {
+        let bars = bars();
+        assert_near(bars.score(0.), CRIT_MULTIPLIER);
+        assert_near(bars.score(12.), CRIT_MULTIPLIER);
+        assert_near(bars.score(50.), 1.);
+        assert_near(bars.score(100.), 0.);
+        assert_near(bars.score(150.), 0.);
+    }
+
+    #[test]
+    fn bar_stopped_in_the_center_is_a_critical_hit() {
+        let mut bars = bars();
+        let mut minigame = new_minigame(FightMinigameKind::Single);
+        minigame.start(&mut bars, &weapon(1));
+        advance(&mut minigame, &mut bars, 10);
+        assert!(minigame.press(&mut bars));
+        assert!(!minigame.press(&mut bars));
+        assert!(bars.done());
+        assert_near(minigame.multiplier(&bars), CRIT_MULTIPLIER);
+    }
+
+    #[test]
+    fn bar_that_crosses_the_whole_fight_bar_misses() {
+        let mut bars = bars();
+        let mut minigame = new_minigame(FightMinigameKind::Reversed);
+        minigame.start(&mut bars, &weapon(1));
+        advance(&mut minigame, &mut bars, 19);
+        assert!(!bars.done());
+        advance(&mut minigame, &mut bars, 1);
+        assert!(bars.done());
+        assert!(bars.missed());
+        assert!(!minigame.press(&mut bars));
+        assert_near(minigame.multiplier(&bars), 0.);
+    }
+
+    #[test]
+    fn staggered_bars_wait_for_the_next_bar_to_arrive() {
+        let mut bars = bars();
+        let mut minigame = new_minigame(FightMinigameKind::Staggered);
+        minigame.start(&mut bars, &weapon(2));
+        advance(&mut minigame, &mut bars, 5);
+        assert!(minigame.press(&mut bars));
+        //the second bar is still 30 pixels left of the fight bar
+        assert!(!minigame.press(&mut bars));
+        advance(&mut minigame, &mut bars, 8);
+        assert!(minigame.press(&mut bars));
+        assert_near(minigame.multiplier(&bars), 1.);
+    }
+
+    #[test]
+    fn missed_bars_lower_the_average() {
+        let mut bars = bars();
+        let mut minigame = new_minigame(FightMinigameKind::Staggered);
+        minigame.start(&mut bars, &weapon(2));
+        advance(&mut minigame, &mut bars, 10);
+        assert!(minigame.press(&mut bars));
+        advance(&mut minigame, &mut bars, 30);
+        assert!(bars.done());
+        assert_near(minigame.multiplier(&bars), CRIT_MULTIPLIER / 2.);
+    }
+
+    #[test]
+    fn rhythm_bars_stop_the_closest_bar_and_reward_all_crits() {
+        let mut bars = bars();
+        let mut minigame = new_minigame(FightMinigameKind::Rhythm);
+        minigame.start(&mut bars, &weapon(2));
+        advance(&mut minigame, &mut bars, 10);
+        assert!(minigame.press(&mut bars));
+        assert_eq!(bars.bars[0].hit, Some(0.));
+        assert!(!bars.bars[1].stopped);
+        advance(&mut minigame, &mut bars, 8);
+        assert!(minigame.press(&mut bars));
+        assert_eq!(bars.bars[1].hit, Some(0.));
+        assert_near(minigame.multiplier(&bars), CRIT_MULTIPLIER + PERFECT_BONUS);
+    }
+
+    #[test]
+    fn rhythm_bonus_needs_every_bar_on_the_crit() {
+        let mut bars = bars();
+        let mut minigame = new_minigame(FightMinigameKind::Rhythm);
+        minigame.start(&mut bars, &weapon(2));
+        advance(&mut minigame, &mut bars, 10);
+        assert!(minigame.press(&mut bars));
+        advance(&mut minigame, &mut bars, 13);
+        assert!(minigame.press(&mut bars));
+        assert_near(minigame.multiplier(&bars), (CRIT_MULTIPLIER + 1.) / 2.);
+    }
+}
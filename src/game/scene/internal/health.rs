@@ -1,11 +1,9 @@
 use bevy::prelude::*;
 
 use crate::game::{
-    data::data::Data, loading::loading::AssetManager, physics::physics_object::PhysicsComponent, player::player::{Player, PlayerStats}, scene::internal::{menu::MenuState, menu_transition::MenuTransition}, sound::sound::SoundPlayer
+    data::data::{Data, DamageKind, SoulMode}, loading::loading::AssetManager, physics::physics_object::PhysicsComponent, player::{inventory::Inventory, player::{Player, PlayerStats}}, scene::internal::{menu::MenuState, menu_transition::MenuTransition}, sound::sound::SoundPlayer
 };
 
-use crate::game::physics::rectangle::Rectangle;
-
 pub struct DamagePlugin;
 impl Plugin for DamagePlugin {
     fn build(&self, app: &mut App) {
@@ -17,31 +15,57 @@ impl Plugin for DamagePlugin {
 #[derive(Component)]
 pub struct Damage {
     pub damage: i32,
+    pub kind: DamageKind,
+}
+
+impl Damage {
+    //blue bullets can be passed by standing still, orange ones by moving
+    //and the cyan soul treats every normal bullet as a blue one
+    pub fn hurts(&self, player: &Player, soul_mode: SoulMode) -> bool {
+        match self.kind {
+            DamageKind::Normal => soul_mode != SoulMode::Cyan || player.moving,
+            DamageKind::Blue => player.moving,
+            DamageKind::Orange => !player.moving,
+        }
+    }
+}
+
+pub fn damage_color(kind: DamageKind) -> Color {
+    match kind {
+        DamageKind::Normal => Color::WHITE,
+        DamageKind::Blue => Color::srgb(0.08, 0.66, 1.0),
+        DamageKind::Orange => Color::srgb(0.99, 0.65, 0.0),
+    }
 }
 
 fn update_damage(
-    mut player_query: Query<(&mut PhysicsComponent, &mut Player)>,
+    mut player_query: Query<(&mut PhysicsComponent, &mut Player, &SoulMode)>,
     mut damage_query: Query<(&mut PhysicsComponent, &mut Damage), Without<Player>>,
     mut player_stats: ResMut<PlayerStats>,
     mut menu_transition: ResMut<MenuTransition>,
+    mut menu_state: ResMut<NextState<MenuState>>,
     mut sounds : ResMut<SoundPlayer>,
+    inventory: Res<Inventory>,
     asset_manager : Res<AssetManager>,
     data: Res<Data>,
 ) {
-    if let Ok((mut physics, mut player)) = player_query.single_mut() {
+    if let Ok((mut physics, mut player, soul_mode)) = player_query.single_mut() {
         if player_stats.invincibility <= 0. {
-            let rect_1 = Rectangle::new_v(physics.position, physics.half_hitbox);
+            let collider = physics.collider();
             for (mut physics_2, mut damage) in damage_query.iter_mut() {
-                let rect_2 = Rectangle::new_v(physics_2.position, physics_2.half_hitbox);
-                if rect_1.intersects(rect_2) {
+                if collider.intersects(&physics_2.collider()) && damage.hurts(&player, *soul_mode) {
                     player_stats.invincibility = data.game.player.iframes;
-                    player_stats.health -= damage.damage;
+                    //every 5 DF takes a point off of each hit
+                    let df = player_stats.df + inventory.bonus_df(&asset_manager.items);
+                    player_stats.health -= i32::max(damage.damage - df / 5, 1);
 
                     sounds.play_sound_once_local(asset_manager.sounds["hurt"].clone());
 
                     if player_stats.health <= 0 {
                         player_stats.health = 0;
-                        menu_transition.new_state(MenuState::Restart);
+                        //dying doesn't wait for the board to settle
+                        menu_transition.new_menu = None;
+                        menu_state.set(MenuState::GameOver);
                     }
                     break;
                 }
@@ -1,5 +1,7 @@
 use bevy::{ecs::system::SystemId, prelude::*};
 
+use crate::game::scene::internal::projectile::{BulletPool, Pooled};
+
 pub struct DespawnPlugin;
 impl Plugin for DespawnPlugin {
     fn build(&self, app: &mut App) {
@@ -36,22 +38,27 @@ fn update_opacity(
 
 fn update_despawn(
     mut commands: Commands,
-    mut despawn_query: Query<(&mut DespawnInTime, Entity)>,
+    mut despawn_query: Query<(&mut DespawnInTime, Entity, Has<Pooled>)>,
+    mut pool: ResMut<BulletPool>,
     time: Res<Time<Fixed>>,
 ) {
-    for (mut d, e) in despawn_query.iter_mut() {
+    for (mut d, e, pooled) in despawn_query.iter_mut() {
         d.timer -= time.delta_secs();
         if d.timer <= 0. {
             if d.effect.is_some() {
                 commands.run_system(d.effect.unwrap());
             }
-            commands.entity(e).despawn();
+            pool.remove(&mut commands, e, pooled);
         }
     }
 }
 
-pub fn despawn_objects(mut query: Query<(&mut DespawnInMenu, Entity)>, mut commands: Commands) {
-    for (mut despawn, e) in query.iter_mut() {
-        commands.entity(e).despawn();
+pub fn despawn_objects(
+    mut query: Query<(&mut DespawnInMenu, Entity, Has<Pooled>)>,
+    mut pool: ResMut<BulletPool>,
+    mut commands: Commands,
+) {
+    for (mut despawn, e, pooled) in query.iter_mut() {
+        pool.remove(&mut commands, e, pooled);
     }
 }
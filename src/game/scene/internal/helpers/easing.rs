@@ -0,0 +1,126 @@
+use std::f32::consts::PI;
+
+use crate::game::data::data::Easing;
+
+//how far the back curves pull back past the start and end
+const BACK_OVERSHOOT: f32 = 1.70158;
+const BACK_IN_OUT_OVERSHOOT: f32 = BACK_OVERSHOOT * 1.525;
+const ELASTIC_PERIOD: f32 = 2.0 * PI / 3.0;
+
+//maps the progress of a tween from 0 to 1 onto the curve, the back and
+//elastic curves go outside of 0 to 1 in between
+pub fn ease(easing: Easing, t: f32) -> f32 {
+    let t = t.clamp(0., 1.);
+    match easing {
+        Easing::Linear => t,
+        Easing::QuadIn => t * t,
+        Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+        Easing::QuadInOut => {
+            if t < 0.5 {
+                return 2.0 * t * t;
+            }
+            1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+        }
+        Easing::CubicIn => t * t * t,
+        Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+        Easing::CubicInOut => {
+            if t < 0.5 {
+                return 4.0 * t * t * t;
+            }
+            1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+        }
+        Easing::BackIn => (BACK_OVERSHOOT + 1.0) * t * t * t - BACK_OVERSHOOT * t * t,
+        Easing::BackOut => {
+            let t = t - 1.0;
+            1.0 + (BACK_OVERSHOOT + 1.0) * t * t * t + BACK_OVERSHOOT * t * t
+        }
+        Easing::BackInOut => {
+            let c = BACK_IN_OUT_OVERSHOOT;
+            if t < 0.5 {
+                return (2.0 * t).powi(2) * ((c + 1.0) * 2.0 * t - c) / 2.0;
+            }
+            ((2.0 * t - 2.0).powi(2) * ((c + 1.0) * (t * 2.0 - 2.0) + c) + 2.0) / 2.0
+        }
+        Easing::ElasticIn => {
+            if t == 0. || t == 1. {
+                return t;
+            }
+            -(2f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * ELASTIC_PERIOD).sin()
+        }
+        Easing::ElasticOut => {
+            if t == 0. || t == 1. {
+                return t;
+            }
+            2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * ELASTIC_PERIOD).sin() + 1.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASINGS: [Easing; 12] = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::BackIn,
+        Easing::BackOut,
+        Easing::BackInOut,
+        Easing::ElasticIn,
+        Easing::ElasticOut,
+    ];
+
+    //every value a curve takes for 100 steps between 0 and 1
+    fn samples(easing: Easing) -> Vec<f32> {
+        (0..=100).map(|i| ease(easing, i as f32 / 100.)).collect()
+    }
+
+    #[test]
+    fn every_curve_starts_at_0_and_ends_at_1() {
+        for easing in EASINGS {
+            assert!(ease(easing, 0.).abs() < 1e-5);
+            assert!((ease(easing, 1.) - 1.).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn progress_outside_0_to_1_is_clamped() {
+        for easing in EASINGS {
+            assert_eq!(ease(easing, -1.), ease(easing, 0.));
+            assert_eq!(ease(easing, 2.), ease(easing, 1.));
+        }
+    }
+
+    #[test]
+    fn back_curves_overshoot() {
+        let back_in = samples(Easing::BackIn);
+        assert!(back_in.iter().any(|v| *v < 0.));
+        assert!(back_in.iter().all(|v| *v <= 1. + 1e-5));
+        let back_out = samples(Easing::BackOut);
+        assert!(back_out.iter().any(|v| *v > 1.));
+        assert!(back_out.iter().all(|v| *v >= -1e-5));
+        let back_in_out = samples(Easing::BackInOut);
+        assert!(back_in_out.iter().any(|v| *v < 0.));
+        assert!(back_in_out.iter().any(|v| *v > 1.));
+    }
+
+    #[test]
+    fn elastic_curves_overshoot() {
+        assert!(samples(Easing::ElasticIn).iter().any(|v| *v < 0.));
+        assert!(samples(Easing::ElasticOut).iter().any(|v| *v > 1.));
+    }
+
+    #[test]
+    fn other_curves_stay_between_0_and_1() {
+        //the quad and cubic curves come before the back and elastic ones
+        for easing in &EASINGS[..7] {
+            let values = samples(*easing);
+            assert!(values.iter().all(|v| *v >= -1e-5 && *v <= 1. + 1e-5));
+        }
+    }
+}
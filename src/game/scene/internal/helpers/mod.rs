@@ -1,2 +1,3 @@
 pub mod despawn;
+pub mod easing;
 pub mod menu_item;
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+
+use crate::game::{
+    data::data::DialogueSet,
+    loading::loading::AssetManager,
+    player::{inventory::Inventory, player::PlayerStats},
+    scene::{
+        battle::BattleEvents,
+        internal::{
+            decisions::Decisions, menu::MenuState, menu_transition::MenuTransition,
+            progress::Progress, text::TextBox,
+        },
+    },
+};
+
+#[allow(clippy::too_many_arguments)]
+pub fn use_item(
+    mut commands: Commands,
+    mut decisions: ResMut<Decisions>,
+    mut text_box: ResMut<TextBox>,
+    mut menu_transition: ResMut<MenuTransition>,
+    mut inventory: ResMut<Inventory>,
+    mut player_stats: ResMut<PlayerStats>,
+    mut progress: ResMut<Progress>,
+    battle_events: Res<BattleEvents>,
+    asset_manager: Res<AssetManager>,
+) {
+    commands.run_system(decisions.remove_decisions.unwrap());
+
+    let selected = inventory.selected;
+    let name = inventory.items.get(selected).cloned().unwrap_or_default();
+    //an item that doesn't exist goes straight to the enemy's turn
+    let Some(item) = asset_manager.items.get(&name).cloned() else {
+        log::warn!("no item named {}", name);
+        commands.run_system(battle_events.advance_attacks);
+        return;
+    };
+    menu_transition.new_state(MenuState::Text);
+    //equipment is worn instead of used up
+    if item.equippable {
+        inventory.equip(selected, item.slot);
+    } else if item.consumable {
+        inventory.take(selected);
+    }
+    progress.items_used.push(item.name.clone());
+
+    let mut dialogue = item.text.clone();
+    if item.heal > 0 {
+        player_stats.health = i32::min(player_stats.health + item.heal, player_stats.max_health);
+        if player_stats.health == player_stats.max_health {
+            dialogue.push("Your HP was maxed out.".to_string());
+        } else {
+            dialogue.push("You recovered ".to_string() + item.heal.to_string().as_str() + " HP!");
+        }
+    }
+
+    text_box.queue_event(
+        DialogueSet {
+            name: item.name.clone(),
+            dialogue,
+        },
+        battle_events.advance_attacks,
+    );
+}
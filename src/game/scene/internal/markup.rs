@@ -0,0 +1,427 @@
+use bevy::prelude::*;
+use rand::{Rng, thread_rng};
+
+use crate::game::state::state::AppState;
+
+pub struct MarkupPlugin;
+impl Plugin for MarkupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (build_glyph_text, update_glyphs.after(build_glyph_text))
+                .run_if(in_state(AppState::Level).or(in_state(AppState::Overworld))),
+        );
+    }
+}
+
+const SHAKE_DISTANCE: f32 = 1.0;
+const WAVE_HEIGHT: f32 = 2.0;
+const WAVE_SPEED: f32 = 8.0;
+//phase difference between neighbouring characters of a wave
+const WAVE_OFFSET: f32 = 0.6;
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum GlyphEffect {
+    #[default]
+    None,
+    Shake,
+    Wave,
+}
+
+#[derive(Clone)]
+pub struct Glyph {
+    pub character: char,
+    pub color: Color,
+    pub effect: GlyphEffect,
+    //seconds into the line at which the glyph is typed out
+    pub time: f32,
+}
+
+//parses a dialogue line into glyphs, the supported tags are
+//[color=yellow]..[/color], [shake]..[/shake], [wave]..[/wave],
+//[speed=n]..[/speed], [instant]..[/instant], [pause=seconds] and [br]
+//tags that are not recognized are written out as they are
+pub fn parse_markup(text: &str, velocity: f32, color: Color) -> Vec<Glyph> {
+    let mut glyphs = Vec::new();
+    let chars: Vec<char> = text.chars().collect();
+
+    let mut colors = vec![color];
+    let mut speeds = vec![velocity];
+    let mut effect = GlyphEffect::None;
+    let mut instant = false;
+    let mut time = 0.;
+
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '['
+            && let Some(end) = chars[i..].iter().position(|c| *c == ']')
+        {
+            let tag: String = chars[i + 1..i + end].iter().collect();
+            let (name, value) = tag.split_once('=').unwrap_or((tag.as_str(), ""));
+            let mut known = true;
+            match name {
+                "color" => match parse_color(value) {
+                    Some(color) => colors.push(color),
+                    None => known = false,
+                },
+                "/color" if colors.len() > 1 => {
+                    colors.pop();
+                }
+                "shake" => effect = GlyphEffect::Shake,
+                "wave" => effect = GlyphEffect::Wave,
+                "/shake" | "/wave" => effect = GlyphEffect::None,
+                "speed" => match value.parse::<f32>() {
+                    Ok(speed) if speed > 0. => speeds.push(speed),
+                    _ => known = false,
+                },
+                "/speed" if speeds.len() > 1 => {
+                    speeds.pop();
+                }
+                "instant" => instant = true,
+                "/instant" => instant = false,
+                "pause" => match value.parse::<f32>() {
+                    Ok(pause) => time += pause,
+                    _ => known = false,
+                },
+                "br" => {
+                    glyphs.push(Glyph {
+                        character: '\n',
+                        color,
+                        effect: GlyphEffect::None,
+                        time,
+                    });
+                }
+                _ => known = false,
+            }
+            if known {
+                i += end + 1;
+                continue;
+            }
+        }
+
+        if !instant {
+            time += 1.0 / speeds[speeds.len() - 1];
+        }
+        glyphs.push(Glyph {
+            character: chars[i],
+            color: colors[colors.len() - 1],
+            effect,
+            time,
+        });
+        i += 1;
+    }
+    glyphs
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    match value {
+        "white" => Some(Color::WHITE),
+        "yellow" => Some(Color::srgb(1.0, 1.0, 0.0)),
+        "red" => Some(Color::srgb(1.0, 0.0, 0.0)),
+        "orange" => Some(Color::srgb(1.0, 0.63, 0.25)),
+        "blue" => Some(Color::srgb(0.0, 0.75, 1.0)),
+        "green" => Some(Color::srgb(0.0, 1.0, 0.0)),
+        "gray" => Some(Color::srgb(0.5, 0.5, 0.5)),
+        "black" => Some(Color::BLACK),
+        _ => Srgba::hex(value).ok().map(Color::from),
+    }
+}
+
+//centers of each glyph from the top left of the text, wrapping at word boundaries
+pub fn layout_glyphs(
+    glyphs: &[Glyph],
+    width: f32,
+    glyph_width: f32,
+    line_height: f32,
+) -> Vec<Vec2> {
+    let mut positions = Vec::new();
+    let mut x = 0.;
+    let mut y = 0.;
+    for i in 0..glyphs.len() {
+        let c = glyphs[i].character;
+        if c == '\n' {
+            positions.push(Vec2::new(x, y));
+            x = 0.;
+            y -= line_height;
+            continue;
+        }
+
+        let word_start = i == 0 || glyphs[i - 1].character.is_whitespace();
+        if !c.is_whitespace() && x > 0. {
+            let mut word_length = 1;
+            if word_start {
+                while i + word_length < glyphs.len()
+                    && !glyphs[i + word_length].character.is_whitespace()
+                {
+                    word_length += 1;
+                }
+            }
+            if x + word_length as f32 * glyph_width > width {
+                x = 0.;
+                y -= line_height;
+            }
+        }
+
+        positions.push(Vec2::new(x + glyph_width / 2.0, y - line_height / 2.0));
+        x += glyph_width;
+    }
+    positions
+}
+
+//text made of one entity per glyph so each character can be colored and animated
+#[derive(Component)]
+pub struct GlyphText {
+    pub text: String,
+    pub glyphs: Vec<Glyph>,
+    pub positions: Vec<Vec2>,
+    pub entities: Vec<Entity>,
+    //glyphs past this are hidden until they are typed out
+    pub revealed: usize,
+
+    pub velocity: f32,
+    //color of the glyphs that are not inside a color tag
+    pub color: Color,
+    pub width: f32,
+    pub glyph_width: f32,
+    pub line_height: f32,
+    pub font: TextFont,
+    pub dirty: bool,
+}
+
+impl GlyphText {
+    pub fn new(font: TextFont, width: f32, glyph_width: f32, line_height: f32) -> Self {
+        Self {
+            text: "".to_string(),
+            glyphs: Vec::new(),
+            positions: Vec::new(),
+            entities: Vec::new(),
+            revealed: 0,
+            velocity: 30.0,
+            color: Color::WHITE,
+            width,
+            glyph_width,
+            line_height,
+            font,
+            dirty: false,
+        }
+    }
+    //returns true when the text changed
+    pub fn set_text(&mut self, text: &String) -> bool {
+        if self.text == *text {
+            return false;
+        }
+        self.text = text.clone();
+        self.glyphs = parse_markup(text, self.velocity, self.color);
+        self.positions = layout_glyphs(
+            &self.glyphs,
+            self.width,
+            self.glyph_width,
+            self.line_height,
+        );
+        self.revealed = 0;
+        self.dirty = true;
+        true
+    }
+    //number of glyphs typed out after `timer` seconds
+    pub fn revealed_at(&self, timer: f32) -> usize {
+        self.glyphs.iter().filter(|g| g.time <= timer).count()
+    }
+    //true once `text` has been laid out and fully typed out after `timer` seconds
+    pub fn finished(&self, text: &String, timer: f32) -> bool {
+        self.text == *text && timer >= self.duration()
+    }
+    //seconds it takes to type out the whole text
+    pub fn duration(&self) -> f32 {
+        if self.glyphs.is_empty() {
+            return 0.;
+        }
+        self.glyphs[self.glyphs.len() - 1].time
+    }
+}
+
+fn build_glyph_text(mut commands: Commands, mut text_query: Query<(Entity, &mut GlyphText)>) {
+    for (e, mut glyph_text) in text_query.iter_mut() {
+        if !glyph_text.dirty {
+            continue;
+        }
+        glyph_text.dirty = false;
+        for i in 0..glyph_text.entities.len() {
+            commands.entity(glyph_text.entities[i]).despawn();
+        }
+
+        let mut entities = Vec::new();
+        for i in 0..glyph_text.glyphs.len() {
+            let glyph = &glyph_text.glyphs[i];
+            let mut character = glyph.character.to_string();
+            if glyph.character == '\n' {
+                character = "".to_string();
+            }
+            let glyph_entity = commands
+                .spawn((
+                    Text2d::new(character),
+                    glyph_text.font.clone(),
+                    TextColor(glyph.color),
+                    Transform::from_translation(glyph_text.positions[i].extend(0.0)),
+                    Visibility::Hidden,
+                ))
+                .id();
+            commands.entity(e).add_child(glyph_entity);
+            entities.push(glyph_entity);
+        }
+        glyph_text.entities = entities;
+    }
+}
+
+fn update_glyphs(
+    text_query: Query<&GlyphText>,
+    mut glyph_query: Query<(&mut Transform, &mut Visibility), Without<GlyphText>>,
+    time: Res<Time<Fixed>>,
+) {
+    let mut rand = thread_rng();
+    for glyph_text in text_query.iter() {
+        for i in 0..glyph_text.entities.len() {
+            let Ok((mut transform, mut visibility)) = glyph_query.get_mut(glyph_text.entities[i])
+            else {
+                continue;
+            };
+            if i < glyph_text.revealed {
+                *visibility = Visibility::Inherited;
+            } else {
+                *visibility = Visibility::Hidden;
+            }
+
+            let mut position = glyph_text.positions[i];
+            match glyph_text.glyphs[i].effect {
+                GlyphEffect::Shake => {
+                    position += Vec2::new(
+                        rand.gen_range(-SHAKE_DISTANCE..=SHAKE_DISTANCE),
+                        rand.gen_range(-SHAKE_DISTANCE..=SHAKE_DISTANCE),
+                    );
+                }
+                GlyphEffect::Wave => {
+                    position.y += f32::sin(
+                        time.elapsed_secs() * WAVE_SPEED - i as f32 * WAVE_OFFSET,
+                    ) * WAVE_HEIGHT;
+                }
+                GlyphEffect::None => {}
+            }
+            transform.translation = position.round().extend(transform.translation.z);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YELLOW: Color = Color::srgb(1.0, 1.0, 0.0);
+    const RED: Color = Color::srgb(1.0, 0.0, 0.0);
+
+    fn parse(text: &str) -> Vec<Glyph> {
+        parse_markup(text, 10.0, Color::WHITE)
+    }
+
+    fn characters(glyphs: &[Glyph]) -> String {
+        glyphs.iter().map(|g| g.character).collect()
+    }
+
+    #[test]
+    fn plain_text_is_typed_out_one_character_at_a_time() {
+        let glyphs = parse("abc");
+        assert_eq!(characters(&glyphs), "abc");
+        assert_eq!(glyphs[0].time, 0.1);
+        assert!((glyphs[2].time - 0.3).abs() < 0.0001);
+        assert!(glyphs.iter().all(|g| g.color == Color::WHITE));
+    }
+
+    #[test]
+    fn nested_colors_return_to_the_outer_color() {
+        let glyphs = parse("a[color=yellow]b[color=red]c[/color]d[/color]e");
+        assert_eq!(characters(&glyphs), "abcde");
+        let colors: Vec<Color> = glyphs.iter().map(|g| g.color).collect();
+        assert_eq!(
+            colors,
+            vec![Color::WHITE, YELLOW, RED, YELLOW, Color::WHITE]
+        );
+    }
+
+    #[test]
+    fn unmatched_closing_tags_are_written_out() {
+        let glyphs = parse("[/color]a[/speed]");
+        assert_eq!(characters(&glyphs), "[/color]a[/speed]");
+        assert!(glyphs.iter().all(|g| g.color == Color::WHITE));
+    }
+
+    #[test]
+    fn hex_colors_are_accepted() {
+        let glyphs = parse("[color=#ff0000]a");
+        assert_eq!(glyphs[0].color, RED);
+    }
+
+    #[test]
+    fn unknown_and_invalid_tags_are_written_out() {
+        assert_eq!(characters(&parse("[b]a[/b]")), "[b]a[/b]");
+        assert_eq!(characters(&parse("[color=nope]a")), "[color=nope]a");
+        assert_eq!(characters(&parse("[speed=0]a")), "[speed=0]a");
+        assert_eq!(characters(&parse("[pause=x]a")), "[pause=x]a");
+    }
+
+    #[test]
+    fn unterminated_tags_are_written_out() {
+        assert_eq!(characters(&parse("a[color=red")), "a[color=red");
+        assert_eq!(characters(&parse("[[wave]a")), "[a");
+    }
+
+    #[test]
+    fn shake_and_wave_only_cover_their_span() {
+        let glyphs = parse("a[shake]b[/shake]c[wave]d[/wave]e");
+        let effects: Vec<GlyphEffect> = glyphs.iter().map(|g| g.effect).collect();
+        assert!(
+            effects
+                == vec![
+                    GlyphEffect::None,
+                    GlyphEffect::Shake,
+                    GlyphEffect::None,
+                    GlyphEffect::Wave,
+                    GlyphEffect::None,
+                ]
+        );
+    }
+
+    #[test]
+    fn speed_pause_and_instant_change_the_timing() {
+        let glyphs = parse("[speed=20]a[/speed]b[pause=1]c[instant]de[/instant]");
+        assert!((glyphs[0].time - 0.05).abs() < 0.0001);
+        assert!((glyphs[1].time - 0.15).abs() < 0.0001);
+        assert!((glyphs[2].time - 1.25).abs() < 0.0001);
+        assert_eq!(glyphs[3].time, glyphs[2].time);
+        assert_eq!(glyphs[4].time, glyphs[2].time);
+    }
+
+    #[test]
+    fn line_break_tag_becomes_a_newline() {
+        let glyphs = parse("a[br]b");
+        assert_eq!(characters(&glyphs), "a\nb");
+        let positions = layout_glyphs(&glyphs, 100.0, 10.0, 20.0);
+        assert_eq!(positions[0], Vec2::new(5.0, -10.0));
+        assert_eq!(positions[2], Vec2::new(5.0, -30.0));
+    }
+
+    #[test]
+    fn words_wrap_to_the_next_line_as_a_whole() {
+        let glyphs = parse("ab cd");
+        let positions = layout_glyphs(&glyphs, 40.0, 10.0, 20.0);
+        assert_eq!(positions[1], Vec2::new(15.0, -10.0));
+        //"cd" would end at 50, past the width of 40
+        assert_eq!(positions[3], Vec2::new(5.0, -30.0));
+        assert_eq!(positions[4], Vec2::new(15.0, -30.0));
+    }
+
+    #[test]
+    fn words_longer_than_a_line_are_split() {
+        let glyphs = parse("abcde");
+        let positions = layout_glyphs(&glyphs, 30.0, 10.0, 20.0);
+        assert_eq!(positions[2], Vec2::new(25.0, -10.0));
+        assert_eq!(positions[3], Vec2::new(5.0, -30.0));
+    }
+}
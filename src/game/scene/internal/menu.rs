@@ -1,29 +1,43 @@
 use bevy::prelude::*;
 
-use crate::game::scene::{
-    attacks::AttacksPlugin,
-    internal::{
-        bullet_board::BulletBoardPlugin,
-        death::{enemy_death::EnemyDeathPlugin, restart_screen::RestartPlugin},
-        decisions::DecisionPlugin,
-        dodging::DodgingPlugin,
-        enemy_health::EnemyHealthPlugin,
-        fight::FightPlugin,
-        health::DamagePlugin,
-        helpers::despawn::DespawnPlugin,
-        menu_transition::MenuTransitionPlugin,
-        opponent::OpponentPlugin,
-        progress::ProgressPlugin,
-        selection::{MenuOption, MenuSelectPlugin},
-        stats::StatsPlugin,
-        text::TextBoxPlugin,
+use crate::game::{
+    scene::{
+        internal::{
+            attack_pattern::AttackPatternPlugin,
+            beam::BeamPlugin,
+            bullet_board::BulletBoardPlugin,
+            death::{
+                enemy_death::EnemyDeathPlugin, game_over::GameOverPlugin,
+                restart_screen::RestartPlugin,
+            },
+            decisions::DecisionPlugin,
+            dodging::DodgingPlugin,
+            emitter::EmitterPlugin,
+            encounter::EncounterPlugin,
+            enemy_health::EnemyHealthPlugin,
+            fight::FightPlugin,
+            health::DamagePlugin,
+            helpers::despawn::DespawnPlugin,
+            markup::MarkupPlugin,
+            menu_transition::MenuTransitionPlugin,
+            mercy::MercyPlugin,
+            opponent::OpponentPlugin,
+            progress::ProgressPlugin,
+            projectile::ProjectilePlugin,
+            selection::{MenuOption, MenuSelectPlugin},
+            speech::SpeechPlugin,
+            stats::StatsPlugin,
+            text::TextBoxPlugin,
+            turn_script::TurnScriptPlugin,
+        },
     },
+    state::state::AppState,
 };
 
 pub struct MenuPlugin;
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<MenuState>()
+        app.add_sub_state::<MenuState>()
             .add_plugins((
                 MenuSelectPlugin,
                 BulletBoardPlugin,
@@ -35,27 +49,42 @@ impl Plugin for MenuPlugin {
                 FightPlugin,
                 OpponentPlugin,
                 ProgressPlugin,
-                AttacksPlugin,
                 DamagePlugin,
                 DespawnPlugin,
                 EnemyHealthPlugin,
                 RestartPlugin,
             ))
-            .add_plugins((EnemyDeathPlugin));
+            .add_plugins((
+                EnemyDeathPlugin,
+                AttackPatternPlugin,
+                EncounterPlugin,
+                MercyPlugin,
+                TurnScriptPlugin,
+                SpeechPlugin,
+                MarkupPlugin,
+                BeamPlugin,
+                EmitterPlugin,
+                ProjectilePlugin,
+                GameOverPlugin,
+            ));
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+//only exists during the battle so its systems stay idle in the overworld
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, SubStates)]
+#[source(AppState = AppState::Level)]
 pub enum MenuState {
     #[default]
     Selection,
 
     Decision,
     Text,
+    Speech,
     Fight,
     Dodging,
 
     EnemyDeath,
+    GameOver,
     Restart,
 
     ERROR,
@@ -0,0 +1,119 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::{Rng, thread_rng};
+
+use crate::game::{
+    loading::loading::AssetManager,
+    physics::physics_object::PhysicsComponent,
+    save::save::Story,
+    scene::{
+        battle::BattleEvents,
+        internal::{
+            decisions::Decisions,
+            encounter::Encounter,
+            helpers::despawn::{DespawnInTime, OpacityFromTimer},
+            menu::MenuState,
+            menu_transition::MenuTransition,
+            opponent::Opponent,
+            progress::Progress,
+            text::TextBox,
+        },
+    },
+    sound::sound::SoundPlayer,
+};
+
+pub struct MercyPlugin;
+impl Plugin for MercyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(MenuState::Restart), reset_spared_visual)
+            .add_systems(OnExit(MenuState::GameOver), reset_spared_visual);
+    }
+}
+
+//color of the names of enemies that can be spared
+pub const SPARE_COLOR: Color = Color::srgb(1.0, 1.0, 0.0);
+
+const SPARE_DUST_COUNT: i32 = 8;
+const SPARE_DUST_SPEED: f32 = 2.0;
+const SPARE_DUST_LIFE: f32 = 0.6;
+
+#[allow(clippy::too_many_arguments)]
+pub fn spare(
+    mut commands: Commands,
+    mut decisions: ResMut<Decisions>,
+    mut progress: ResMut<Progress>,
+    mut sounds: ResMut<SoundPlayer>,
+    mut story: ResMut<Story>,
+    mut opponent_query: Query<(&Opponent, &PhysicsComponent, &mut Sprite)>,
+    battle_events: Res<BattleEvents>,
+    encounter: Res<Encounter>,
+    asset_manager: Res<AssetManager>,
+) {
+    commands.run_system(decisions.remove_decisions.unwrap());
+    let mut spared = false;
+    for (opponent, physics, mut sprite) in opponent_query.iter_mut() {
+        let enemy = &progress.enemies[opponent.index];
+        if enemy.killed || enemy.spared {
+            continue;
+        }
+        if encounter.spareable(opponent.index, &progress) {
+            progress.enemies[opponent.index].spared = true;
+            story.spares += 1;
+            sprite.color.set_alpha(0.5);
+            spawn_spare_dust(&mut commands, physics.position);
+            spared = true;
+        }
+    }
+    if spared {
+        sounds.play_sound_once_local(asset_manager.sounds["spare"].clone());
+    }
+
+    if progress.finished() {
+        commands.run_system(battle_events.events["win_battle"]);
+    } else {
+        commands.run_system(battle_events.advance_attacks);
+    }
+}
+
+fn spawn_spare_dust(commands: &mut Commands, position: Vec2) {
+    for i in 0..SPARE_DUST_COUNT {
+        let angle = i as f32 / SPARE_DUST_COUNT as f32 * TAU;
+        let velocity = Vec2::from_angle(angle) * SPARE_DUST_SPEED;
+        commands.spawn((
+            Sprite::from_color(Color::WHITE, Vec2::splat(6.0)),
+            Transform::from_translation(position.extend(2.0)),
+            PhysicsComponent::new_full(position, velocity, Vec2::ZERO, Vec2::ZERO),
+            DespawnInTime::new(SPARE_DUST_LIFE, None),
+            OpacityFromTimer,
+        ));
+    }
+}
+
+pub fn flee(
+    mut commands: Commands,
+    mut decisions: ResMut<Decisions>,
+    mut text_box: ResMut<TextBox>,
+    mut menu_transition: ResMut<MenuTransition>,
+    battle_events: Res<BattleEvents>,
+    encounter: Res<Encounter>,
+    asset_manager: Res<AssetManager>,
+) {
+    commands.run_system(decisions.remove_decisions.unwrap());
+    let mut rand = thread_rng();
+    if rand.gen_range(0.0..1.0) < encounter.data.flee_chance {
+        menu_transition.new_state(MenuState::Text);
+        text_box.queue_event(
+            asset_manager.dialogue_storage["flee"].clone(),
+            battle_events.events["end_battle"],
+        );
+    } else {
+        commands.run_system(battle_events.advance_attacks);
+    }
+}
+
+fn reset_spared_visual(mut opponent_query: Query<&mut Sprite, With<Opponent>>) {
+    for mut sprite in opponent_query.iter_mut() {
+        sprite.color.set_alpha(1.0);
+    }
+}
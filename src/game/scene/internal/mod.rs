@@ -1,17 +1,29 @@
+pub mod act;
 pub mod attack;
+pub mod attack_pattern;
+pub mod beam;
 pub mod bullet_board;
 pub mod death;
 pub mod decisions;
 pub mod dodging;
+pub mod emitter;
+pub mod encounter;
 pub mod enemy_health;
 pub mod fight;
+pub mod fight_minigame;
 pub mod health;
 pub mod helpers;
+pub mod items;
+pub mod markup;
 pub mod menu;
 pub mod menu_transition;
+pub mod mercy;
 pub mod opponent;
 pub mod progress;
+pub mod projectile;
 pub mod scene;
 pub mod selection;
+pub mod speech;
 pub mod stats;
 pub mod text;
+pub mod turn_script;
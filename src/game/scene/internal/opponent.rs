@@ -1,6 +1,9 @@
+use std::f32::consts::PI;
+
 use bevy::{prelude::*, text::TextLayoutInfo};
 
 use crate::game::{
+    animation::animation::Animator,
     data::data::Data,
     loading::loading::AssetManager,
     physics::physics_object::PhysicsComponent,
@@ -8,12 +11,14 @@ use crate::game::{
         battle::spawn_opponent,
         internal::{
             bullet_board::BulletBoard,
+            encounter::Encounter,
             enemy_health::EnemyHealthBar,
             fight::FightManager,
             helpers::menu_item::MenuItem,
             stats::{HealthBar, HealthBarType},
         },
     },
+    sound::sound::SoundPlayer,
     state::state::AppState,
 };
 
@@ -27,8 +32,10 @@ impl Plugin for OpponentPlugin {
             )
             .add_systems(
                 FixedUpdate,
-                update_opponent_position
-                    .before(update_enemy_healthbar)
+                (
+                    update_opponent_position.before(update_enemy_healthbar),
+                    update_opponent_hurt.after(update_opponent_position),
+                )
                     .run_if(in_state(AppState::Level)),
             )
             .add_systems(
@@ -52,34 +59,95 @@ pub struct OpponentHealthBarManager {
 #[derive(Component)]
 pub struct Opponent {
     pub offset: Vec2,
+    //position of the enemy in the encounter
+    pub index: usize,
+    //set once the current attack landed or missed, so its sound plays once
+    pub hurt: bool,
+    pub sprite: Handle<Image>,
+    pub hurt_sprite: Option<Handle<Image>>,
 }
 
 fn update_opponent_position(
     bullet_board: Res<BulletBoard>,
     mut opponent_query: Query<(&mut Opponent, &mut PhysicsComponent)>,
     mut fight_manager: ResMut<FightManager>,
+    encounter: Res<Encounter>,
     data: Res<Data>,
 ) {
     for (mut opponent, mut physics) in opponent_query.iter_mut() {
-        physics.position.x = 0.;
+        physics.position.x = encounter.get_offset(opponent.index);
         physics.position.y = bullet_board.position.y
             + bullet_board.height / 2.0
             + bullet_board.border
             + 10.0
-            + data.game.opponent_data.height * 2.0 / 2.0;
-        if fight_manager.strike && !fight_manager.miss {
+            + encounter.get_enemy(opponent.index).height * 2.0 / 2.0;
+        opponent.offset = Vec2::ZERO;
+        if fight_manager.strike && !fight_manager.miss && opponent.index == encounter.target {
             if fight_manager.attack_animation <= 1.0 {
+                let hit = &encounter.get_enemy(opponent.index).hit;
                 let time = 1.0 - fight_manager.attack_animation;
-                let shake_speed = 10.0;
+                //the shake starts at full width and settles as the animation runs out
+                let decay = fight_manager.attack_animation.max(0.).powf(hit.shake_decay);
                 opponent.offset = Vec2::X
-                    * f32::sin(time * 2.0 * 3.14159 * shake_speed)
-                    * 10.0
-                    * fight_manager.attack_animation;
+                    * f32::sin(time * 2.0 * PI * hit.shake_speed)
+                    * hit.shake
+                    * decay;
                 physics.position += opponent.offset;
             }
         }
     }
 }
+//shows the hurt sprite or animation while the enemy shakes and plays its hit sounds
+fn update_opponent_hurt(
+    mut opponent_query: Query<(&mut Opponent, &mut Sprite, Option<&mut Animator>)>,
+    mut sounds: ResMut<SoundPlayer>,
+    fight_manager: Res<FightManager>,
+    encounter: Res<Encounter>,
+    asset_manager: Res<AssetManager>,
+) {
+    for (mut opponent, mut sprite, animator) in opponent_query.iter_mut() {
+        let enemy = encounter.get_enemy(opponent.index);
+        let targeted = fight_manager.strike && opponent.index == encounter.target;
+        //damage lands when the slash is over, a miss shows right away
+        let landed =
+            targeted && (fight_manager.miss || fight_manager.attack_animation <= 1.0);
+        if !targeted {
+            opponent.hurt = false;
+        }
+        if landed && !opponent.hurt {
+            opponent.hurt = true;
+            let mut sound = &enemy.hit.sound;
+            if fight_manager.miss {
+                sound = &enemy.hit.miss_sound;
+            }
+            if let Some(sound) = asset_manager.sounds.get(sound) {
+                sounds.play_sound_once_local(sound.clone());
+            } else if !sound.is_empty() {
+                log::error!("enemy {} uses unknown sound {}", enemy.name, sound);
+            }
+        }
+
+        let hurt = landed && !fight_manager.miss && fight_manager.attack_animation > 0.;
+        let mut image = &opponent.sprite;
+        if let Some(hurt_sprite) = &opponent.hurt_sprite
+            && hurt
+        {
+            image = hurt_sprite;
+        }
+        if sprite.image != *image {
+            sprite.image = image.clone();
+        }
+        if let Some(mut animator) = animator {
+            let mut animation = "idle".to_string();
+            if hurt && animator.animation_bank.contains_key(&enemy.hit.hurt_animation) {
+                animation = enemy.hit.hurt_animation.clone();
+            }
+            if animator.animation_bank.contains_key(&animation) {
+                animator.current_animation = animation;
+            }
+        }
+    }
+}
 fn update_text_color(
     mut fight_manager: ResMut<FightManager>,
     mut text_query: Query<(&mut DamageText, &mut TextColor)>,
@@ -117,17 +185,22 @@ fn update_enemy_healthbar(
         Without<OpponentHealthBar>,
     >,
     mut writer: Text2dWriter,
+    encounter: Res<Encounter>,
     data: Res<Data>,
 ) {
-    if let Ok((mut o, mut physics)) = opponent_query.single_mut() {
+    for (mut o, mut physics) in opponent_query.iter_mut() {
+        if o.index != encounter.target {
+            continue;
+        }
+        let enemy = encounter.get_target();
         let mut healthbar_height = 0.0;
         let mut healthbar_pos = Vec2::ZERO;
         for (mut h_t, mut h, mut b, mut v, mut t) in bar_query.iter_mut() {
             if fight_manager.strike {
                 if fight_manager.attack_animation <= 1.0 || fight_manager.miss {
-                    h.max_health = data.game.opponent_data.health;
+                    h.max_health = enemy.health;
                     h.position = physics.position - o.offset
-                        + Vec2::new(0., data.game.opponent_data.height * 2.0 / 2.0)
+                        + Vec2::new(0., enemy.height * 2.0 / 2.0)
                         + Vec2::new(0., h.custom_size.unwrap().y as f32 / 2.0);
                     let diff = bar_manager.old_health - bar_manager.new_health;
                     h.health = (bar_manager.new_health as f32
@@ -156,7 +229,7 @@ fn update_enemy_healthbar(
                             f32::clamp((1.0 - fight_manager.attack_animation) * 2.0, 0.0, 1.0);
                         position.y -= ((f32::cos(time * 2.0 * 3.14159) - 1.0) / 2.0) * 12.0;
                     } else {
-                        *writer.text(e, 0) = "MISS".to_string();
+                        *writer.text(e, 0) = enemy.hit.miss_text.clone();
                     }
                     t.translation = position.round().extend(5.0) + Vec3::Y * 0.3;
                     *v = Visibility::Visible;
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use bevy::prelude::*;
 
 pub struct ProgressPlugin;
@@ -7,17 +9,53 @@ impl Plugin for ProgressPlugin {
     }
 }
 
+#[derive(Clone, Default)]
+pub struct EnemyProgress {
+    pub health: i32,
+    pub killed: bool,
+    pub spared: bool,
+    //raised by ACTs, the enemy can be spared once it reaches the enemy's spare_mercy
+    pub mercy: i32,
+
+    //current stats, changed by ACTs during the battle
+    pub at: i32,
+    pub df: i32,
+}
+
 #[derive(Resource)]
 pub struct Progress {
     pub turns: i32,
-    pub health: i32,
+    pub enemies: Vec<EnemyProgress>,
+    //flags set by ACTs during this battle
+    pub flags: HashSet<String>,
+    //names of the items used during this battle
+    pub items_used: Vec<String>,
 }
 
 impl Default for Progress {
     fn default() -> Self {
         Self {
             turns: 0,
-            health: 1,
+            enemies: Vec::new(),
+            flags: HashSet::new(),
+            items_used: Vec::new(),
         }
     }
 }
+
+impl Progress {
+    //indices of the enemies that are still fighting
+    pub fn active_enemies(&self) -> Vec<usize> {
+        let mut active = Vec::new();
+        for i in 0..self.enemies.len() {
+            if !self.enemies[i].killed && !self.enemies[i].spared {
+                active.push(i);
+            }
+        }
+        active
+    }
+    //the battle is over once every enemy is either killed or spared
+    pub fn finished(&self) -> bool {
+        self.active_enemies().is_empty()
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+use crate::game::{
+    data::data::Data,
+    physics::{motion::Motion, physics_object::PhysicsComponent},
+    scene::internal::{
+        bullet_board::BulletBoard,
+        health::Damage,
+        helpers::despawn::{DespawnInMenu, DespawnInTime, OpacityFromTimer},
+    },
+    state::state::AppState,
+};
+
+pub struct ProjectilePlugin;
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BulletPool>()
+            .add_systems(OnExit(AppState::Level), clear_pool)
+            .add_systems(
+                FixedPostUpdate,
+                cull_projectiles.run_if(in_state(AppState::Level)),
+            );
+    }
+}
+
+//bullets spawned through the pool, they are hidden and reused instead of despawned
+#[derive(Component)]
+pub struct Pooled;
+
+//keeps a bullet alive when it leaves the board, for things that start far out
+#[derive(Component)]
+pub struct NoCulling;
+
+#[derive(Resource, Default)]
+pub struct BulletPool {
+    free: Vec<Entity>,
+}
+
+impl BulletPool {
+    //puts the bundle on a released bullet, or spawns a new one when there are none
+    pub fn spawn<B: Bundle>(&mut self, commands: &mut Commands, bundle: B) -> Entity {
+        if let Some(bullet) = self.free.pop() {
+            commands
+                .entity(bullet)
+                .insert((bundle, Visibility::Inherited));
+            return bullet;
+        }
+        commands.spawn((bundle, Pooled)).id()
+    }
+    //hides the bullet and takes away everything that makes it act like one
+    pub fn release(&mut self, commands: &mut Commands, bullet: Entity) {
+        if self.free.contains(&bullet) {
+            return;
+        }
+        commands
+            .entity(bullet)
+            .remove::<(
+                Damage,
+                Motion,
+                DespawnInTime,
+                DespawnInMenu,
+                OpacityFromTimer,
+                NoCulling,
+            )>()
+            .insert((PhysicsComponent::default(), Visibility::Hidden));
+        self.free.push(bullet);
+    }
+    //removes the bullet, returning it to the pool when it came from there
+    pub fn remove(&mut self, commands: &mut Commands, bullet: Entity, pooled: bool) {
+        if pooled {
+            self.release(commands, bullet);
+        } else {
+            commands.entity(bullet).despawn();
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn cull_projectiles(
+    mut commands: Commands,
+    mut pool: ResMut<BulletPool>,
+    bullet_query: Query<
+        (Entity, &PhysicsComponent, Has<Pooled>),
+        (With<Damage>, Without<NoCulling>, Without<ChildOf>),
+    >,
+    bullet_board: Res<BulletBoard>,
+    data: Res<Data>,
+) {
+    let margin = data.game.battle.cull_margin;
+    if margin <= 0. {
+        return;
+    }
+    let corners = bullet_board.arena_corners();
+    let mut min = Vec2::MAX;
+    let mut max = Vec2::MIN;
+    for corner in corners.iter().flatten() {
+        min = min.min(*corner);
+        max = max.max(*corner);
+    }
+    min -= Vec2::splat(margin);
+    max += Vec2::splat(margin);
+    for (e, physics, pooled) in bullet_query.iter() {
+        //long hitboxes like beams stay as long as any part of them could be close
+        let reach = physics.half_hitbox.length();
+        let outside = (min - physics.position)
+            .max(physics.position - max)
+            .max(Vec2::ZERO);
+        if outside.length() > reach {
+            pool.remove(&mut commands, e, pooled);
+        }
+    }
+}
+
+//the bullets still in use are despawned with the rest of the battle
+fn clear_pool(mut commands: Commands, mut pool: ResMut<BulletPool>) {
+    for i in 0..pool.free.len() {
+        commands.entity(pool.free[i]).despawn();
+    }
+    pool.free.clear();
+}
@@ -35,6 +35,10 @@ pub struct Platform {
     pub web: bool,
 }
 
+//the camera that renders the game before it is scaled to the window
+#[derive(Component)]
+pub struct MainCamera;
+
 #[derive(Resource)]
 pub struct Resolution {
     pub game_res: Vec2,
@@ -113,6 +117,7 @@ fn setup(
             },
             Msaa::Off,
             render_layers.pre.clone(),
+            MainCamera,
         ))
         .id();
     render_image(&mut commands, &image, render_layers.downscaled.clone(), 1.0);
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 
 use crate::game::{
-    animation::animation::Animator, data::data::Data, loading::loading::AssetManager, physics::physics_object::PhysicsComponent, player::player::Player, scene::internal::{
+    animation::animation::Animator, data::data::Data, loading::loading::AssetManager, physics::physics_object::PhysicsComponent, player::{inventory::Inventory, player::Player}, scene::internal::{
         bullet_board::BulletBoard, decisions::update_decisions, helpers::{despawn::DespawnInMenu, menu_item::MenuItem}, menu::MenuState, menu_transition::MenuTransition, stats::{HealthBar, HealthBarType}, text::TextBox
     }, sound::sound::SoundPlayer, state::state::AppState
 };
@@ -22,7 +22,30 @@ impl MenuSelect {
     pub fn get_option(&mut self) -> MenuOption {
         self.selections[self.selection as usize].clone()
     }
+    pub fn reset(&mut self) {
+        self.selection = 0;
+    }
+    //center of a button, they are laid out from the left edge of the board
+    pub fn button_position(&self, index: usize, bullet_board: &BulletBoard) -> Vec2 {
+        let mut x = -bullet_board.width / 2.0 - bullet_board.border + self.button_width / 2.0;
+        for spacing in &BUTTON_SPACING[..index] {
+            x += spacing + self.button_width;
+        }
+        Vec2::new(x.floor(), BUTTON_Y)
+    }
+    //where the soul sits while the button is hovered
+    pub fn soul_position(&self, index: usize, bullet_board: &BulletBoard, data: &Data) -> Vec2 {
+        let button = self.button_position(index, bullet_board);
+        Vec2::new(
+            button.x - self.button_width / 2.0 + 8.0 + data.game.player.sprite_size_x / 2.0,
+            button.y,
+        )
+    }
 }
+
+const BUTTON_SPACING: [f32; 4] = [43.0, 50.0, 45.0, 0.0];
+const BUTTON_Y: f32 = -213.0;
+
 #[derive(Default, PartialEq, Component, Clone, Eq, Hash)]
 pub enum MenuOption {
     #[default]
@@ -73,6 +96,7 @@ fn update_selection(
     mut text_box: ResMut<TextBox>,
     mut sounds : ResMut<SoundPlayer>,
     asset_manager : Res<AssetManager>,
+    inventory: Res<Inventory>,
 ) {
     if input.just_pressed(KeyCode::ArrowLeft) {
         menu.cycle(-1);
@@ -82,6 +106,10 @@ fn update_selection(
         menu.cycle(1);
         sounds.play_sound_once_local(asset_manager.sounds["move_menu"].clone());
     }
+    //there is nothing to pick from an empty inventory
+    if menu.get_option() == MenuOption::Item && inventory.items.is_empty() {
+        return;
+    }
     if input.just_pressed(KeyCode::KeyZ) {
         menu_transition.new_state(MenuState::Decision);
         sounds.play_sound_once_local(asset_manager.sounds["select"].clone());
@@ -118,7 +146,6 @@ pub fn spawn_buttons(
     asset_manager: Res<AssetManager>,
     data: Res<Data>,
 ) {
-    let mut current_pos = -bullet_board.width / 2.0 - bullet_board.border + menu.button_width / 2.0;
     let mut sprites = vec![
         "sprites/fightbutton.png",
         "sprites/actbutton.png",
@@ -126,11 +153,10 @@ pub fn spawn_buttons(
         "sprites/mercybutton.png",
     ];
 
-    let mut spacing = vec![43.0, 50.0, 45.0, 0.0];
     for i in 0..menu.selections.len() {
         commands.spawn((
             Transform {
-                translation: Vec3::new(current_pos.floor(), -213.0, 0.),
+                translation: menu.button_position(i, &bullet_board).extend(0.),
                 ..Default::default()
             },
             Sprite {
@@ -149,6 +175,5 @@ pub fn spawn_buttons(
             menu.selections[i].clone(),
             MenuItem,
         ));
-        current_pos += spacing[i] + menu.button_width;
     }
 }
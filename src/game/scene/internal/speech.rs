@@ -0,0 +1,216 @@
+use std::f32::consts::FRAC_PI_4;
+
+use bevy::{ecs::system::SystemId, prelude::*};
+
+use crate::game::{
+    data::data::{BubbleSide, Data, DialogueSet},
+    loading::loading::AssetManager,
+    physics::physics_object::PhysicsComponent,
+    scene::internal::{
+        encounter::Encounter,
+        helpers::menu_item::MenuItem,
+        markup::GlyphText,
+        menu::MenuState,
+        opponent::Opponent,
+        text::{hide_player, show_player},
+    },
+    sound::sound::SoundPlayer,
+};
+
+pub struct SpeechPlugin;
+impl Plugin for SpeechPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpeechBubble>()
+            .add_systems(OnEnter(MenuState::Speech), (spawn_speech_bubble, hide_player))
+            .add_systems(OnExit(MenuState::Speech), (despawn_speech_bubble, show_player))
+            .add_systems(Update, update_speech.run_if(in_state(MenuState::Speech)))
+            .add_systems(
+                FixedUpdate,
+                update_speech_text.run_if(in_state(MenuState::Speech)),
+            );
+    }
+}
+
+//characters revealed per second
+const SPEECH_VELOCITY: f32 = 20.0;
+//gap between the enemy sprite and the bubble
+const SPEECH_MARGIN: f32 = 16.0;
+const SPEECH_PADDING: f32 = 8.0;
+
+//lines an enemy says in a bubble next to its sprite
+#[derive(Resource, Default)]
+pub struct SpeechBubble {
+    pub dialogue: Option<DialogueSet>,
+    pub dialogue_index: i32,
+    pub dialogue_end_event: Option<SystemId>,
+    //index of the enemy that is talking
+    pub speaker: usize,
+
+    pub timer: f32,
+    pub prev_length: i32,
+    pub entity: Option<Entity>,
+    pub text_entity: Option<Entity>,
+}
+
+impl SpeechBubble {
+    pub fn queue_event(&mut self, dialogue: DialogueSet, speaker: usize, event: SystemId) {
+        self.dialogue = Some(dialogue);
+        self.dialogue_index = 0;
+        self.dialogue_end_event = Some(event);
+        self.speaker = speaker;
+        self.prev_length = 0;
+        self.timer = 0.;
+    }
+    //an empty set has no line to show, update_speech ends it right away
+    fn line(&self) -> String {
+        if let Some(dialogue) = &self.dialogue
+            && let Some(line) = dialogue.dialogue.get(self.dialogue_index as usize)
+        {
+            return line.clone();
+        }
+        "".to_string()
+    }
+}
+
+fn spawn_speech_bubble(
+    mut commands: Commands,
+    mut speech: ResMut<SpeechBubble>,
+    opponent_query: Query<(&Opponent, &PhysicsComponent)>,
+    encounter: Res<Encounter>,
+    asset_manager: Res<AssetManager>,
+    data: Res<Data>,
+) {
+    let enemy = encounter.get_enemy(speech.speaker);
+    let bubble = &enemy.bubble;
+    //the bubble is written silently when the voice is missing
+    if !bubble.voice.is_empty() && !asset_manager.sounds.contains_key(&bubble.voice) {
+        log::error!("enemy {} uses unknown voice {}", enemy.name, bubble.voice);
+    }
+    let mut position = Vec2::ZERO;
+    for (opponent, physics) in opponent_query.iter() {
+        if opponent.index == speech.speaker {
+            position = physics.position;
+        }
+    }
+
+    let mut side = 1.0;
+    if bubble.side == BubbleSide::Left {
+        side = -1.0;
+    }
+    position.x += side * (enemy.width * 2.0 / 2.0 + SPEECH_MARGIN + bubble.width / 2.0);
+
+    let text_font = TextFont {
+        font: asset_manager.fonts["fonts/DTM-Mono.ttf"].clone(),
+        font_size: 18.0,
+        font_smoothing: bevy::text::FontSmoothing::None,
+        ..Default::default()
+    };
+    let font_size = text_font.font_size;
+    let mut glyph_text = GlyphText::new(
+        text_font,
+        bubble.width - SPEECH_PADDING * 2.0,
+        font_size * data.game.dialogue.glyph_width,
+        font_size * data.game.dialogue.line_height,
+    );
+    glyph_text.velocity = SPEECH_VELOCITY;
+    glyph_text.color = Color::BLACK;
+    let mut text_entity = None;
+    let e = commands
+        .spawn((
+            Sprite::from_color(Color::WHITE, Vec2::new(bubble.width, bubble.height)),
+            Transform::from_translation(position.round().extend(4.0)),
+            Visibility::Visible,
+            Name::new("speech bubble"),
+            MenuItem,
+        ))
+        .with_children(|builder| {
+            //the tail points back at the enemy
+            builder.spawn((
+                Sprite::from_color(Color::WHITE, Vec2::splat(SPEECH_MARGIN)),
+                Transform::from_translation(Vec3::new(-side * bubble.width / 2.0, 0., 0.))
+                    .with_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+            ));
+            let text = builder
+                .spawn((
+                    glyph_text,
+                    Visibility::Inherited,
+                    Transform::from_translation(Vec3::new(
+                        -bubble.width / 2.0 + SPEECH_PADDING,
+                        bubble.height / 2.0 - SPEECH_PADDING,
+                        1.0,
+                    )),
+                ))
+                .id();
+            text_entity = Some(text);
+        })
+        .id();
+    speech.entity = Some(e);
+    speech.text_entity = text_entity;
+}
+
+fn despawn_speech_bubble(mut commands: Commands, mut speech: ResMut<SpeechBubble>) {
+    if let Some(e) = speech.entity.take() {
+        commands.entity(e).despawn();
+    }
+    speech.text_entity = None;
+}
+
+fn update_speech(
+    mut commands: Commands,
+    mut speech: ResMut<SpeechBubble>,
+    text_query: Query<&GlyphText>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    if speech.dialogue.is_none() || speech.text_entity.is_none() {
+        return;
+    }
+    let Ok(glyph_text) = text_query.get(speech.text_entity.unwrap()) else {
+        return;
+    };
+    let typed = speech.timer >= glyph_text.duration();
+    if keys.just_pressed(KeyCode::KeyX) {
+        speech.timer = 1000.0;
+    }
+    //the bubble only moves on once the line is fully written
+    if keys.just_pressed(KeyCode::KeyZ) && typed {
+        speech.timer = 0.;
+        speech.prev_length = 0;
+        speech.dialogue_index += 1;
+    }
+
+    let len = speech.dialogue.as_ref().unwrap().dialogue.len();
+    if speech.dialogue_index >= len as i32 {
+        speech.dialogue = None;
+        commands.run_system(speech.dialogue_end_event.unwrap());
+    }
+}
+
+fn update_speech_text(
+    mut text_query: Query<&mut GlyphText>,
+    mut speech: ResMut<SpeechBubble>,
+    mut sounds: ResMut<SoundPlayer>,
+    time: Res<Time<Fixed>>,
+    encounter: Res<Encounter>,
+    asset_manager: Res<AssetManager>,
+) {
+    if speech.text_entity.is_none() {
+        return;
+    }
+    let Ok(mut glyph_text) = text_query.get_mut(speech.text_entity.unwrap()) else {
+        return;
+    };
+    if glyph_text.set_text(&speech.line()) {
+        speech.prev_length = 0;
+    }
+    speech.timer += time.delta_secs();
+    let length = glyph_text.revealed_at(speech.timer) as i32;
+
+    if length > speech.prev_length {
+        speech.prev_length = length;
+        let voice = &encounter.get_enemy(speech.speaker).bubble.voice;
+        if let Some(sound) = asset_manager.sounds.get(voice) {
+            sounds.play_sound_once_local(sound.clone());
+        }
+    }
+    glyph_text.revealed = length as usize;
+}
@@ -1,7 +1,6 @@
 use bevy::{prelude::*, text::TextBounds};
 
 use crate::game::{
-    data::data::Data,
     loading::loading::AssetManager,
     player::player::PlayerStats,
     scene::internal::{bullet_board::BulletBoard, helpers::menu_item::MenuItem},
@@ -206,21 +205,25 @@ fn update_health_bar(
 fn update_name(
     mut writer: Text2dWriter,
     mut name_query: Query<(Entity), With<PlayerStatsText>>,
-    data: Res<Data>,
+    player_stats: Res<PlayerStats>,
 ) {
     if let Ok(e) = name_query.single() {
-        *writer.text(e, 0) = data.game.player.name.clone() + "   " + "LV 1";
+        *writer.text(e, 0) =
+            player_stats.name.clone() + "   LV " + player_stats.lv.to_string().as_str();
     }
 }
 
 fn update_hp_text(
     mut writer: Text2dWriter,
-    mut hp_query: Query<(Entity), With<HealthText>>,
+    mut hp_query: Query<(Entity, &mut Transform), With<HealthText>>,
     player_stats: Res<PlayerStats>,
 ) {
-    if let Ok(e) = hp_query.single() {
+    if let Ok((e, mut t)) = hp_query.single_mut() {
         *writer.text(e, 0) =
             player_stats.health.to_string() + " / " + player_stats.max_health.to_string().as_str();
+        //stays next to the bar when the max HP goes up
+        let healthbar_width = 1.0 + player_stats.max_health as f32 * 1.2;
+        t.translation.x = 245. + healthbar_width + 14.;
     }
 }
 
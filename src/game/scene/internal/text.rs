@@ -1,12 +1,15 @@
-use bevy::{ecs::system::SystemId, prelude::*, text::TextBounds};
+use bevy::{ecs::system::SystemId, prelude::*};
 
 use crate::game::{
     camera::render_layers::RenderLayerStorage, data::data::{Data, DialogueSet}, loading::loading::AssetManager, player::player::Player, scene::internal::{
         bullet_board::{spawn_bullet_board, BulletBoard, BulletBoardFill},
         helpers::menu_item::MenuItem,
+        encounter::Encounter,
+        markup::GlyphText,
         menu::MenuState,
         menu_transition::MenuTransition,
         progress::Progress,
+        turn_script::TurnPlan,
     }, sound::sound::SoundPlayer, state::state::AppState
 };
 
@@ -72,17 +75,24 @@ fn refresh_text(
     asset_manager: Res<AssetManager>,
     data: Res<Data>,
     progress: Res<Progress>,
+    encounter: Res<Encounter>,
     mut text_box: ResMut<TextBox>,
+    mut turn_plan: ResMut<TurnPlan>,
 ) {
-    let dialogue_list = &data.game.battle.dialogues;
-    let dialogue_name = &dialogue_list[progress.turns as usize];
+    turn_plan.plan_text(&encounter, &progress);
+    let dialogue_name = &turn_plan.text;
     text_box.prev_length = 0;
-    text_box.set_text(
-        "* ".to_string()
-            + asset_manager.dialogue_storage[dialogue_name].dialogue[0]
-                .clone()
-                .as_str(),
-    );
+    //the box is left empty when the flavor text doesn't exist
+    let Some(line) = asset_manager
+        .dialogue_storage
+        .get(dialogue_name)
+        .and_then(|set| set.dialogue.first())
+    else {
+        log::warn!("no flavor text named {}", dialogue_name);
+        text_box.set_text("".to_string());
+        return;
+    };
+    text_box.set_text("* ".to_string() + line.as_str());
 }
 impl TextBox {
     pub fn clear_box(&mut self) {
@@ -122,28 +132,34 @@ fn update_dialogue(
         }
     }
 }
-fn hide_player(mut player_query: Query<(&mut Visibility), With<Player>>) {
+pub fn hide_player(mut player_query: Query<(&mut Visibility), With<Player>>) {
     if let Ok(mut v) = player_query.single_mut() {
         *v = Visibility::Hidden;
     }
 }
 
-fn show_player(mut player_query: Query<(&mut Visibility), With<Player>>) {
+pub fn show_player(mut player_query: Query<(&mut Visibility), With<Player>>) {
     if let Ok(mut v) = player_query.single_mut() {
         *v = Visibility::Visible;
     }
 }
 fn update_text(
-    mut writer: Text2dWriter, mut text_box: ResMut<TextBox>,
+    mut text_query: Query<&mut GlyphText>,
+    mut text_box: ResMut<TextBox>,
     time: Res<Time<Fixed>>,
     mut sounds : ResMut<SoundPlayer>,    
     asset_manager: Res<AssetManager>
 ) {
     if text_box.entity.is_some() {
         text_box.timer += time.delta_secs();
-        let mut length = (text_box.velocity * text_box.timer) as i32;
-        length = i32::clamp(length, 0, text_box.text.len() as i32);
-
+        let Ok(mut glyph_text) = text_query.get_mut(text_box.entity.unwrap()) else {
+            return;
+        };
+        glyph_text.velocity = text_box.velocity;
+        if glyph_text.set_text(&text_box.text) {
+            text_box.prev_length = 0;
+        }
+        let length = glyph_text.revealed_at(text_box.timer) as i32;
 
         if (length as i32 - text_box.prev_length) > 1 {
 
@@ -151,9 +167,7 @@ fn update_text(
             
             sounds.play_sound_once_local(asset_manager.sounds["text"].clone());
         }
-        let s = &text_box.text;
-        let display = &s[0..(length as usize)];
-        *writer.text(text_box.entity.unwrap(), 0) = display.to_string();
+        glyph_text.revealed = length as usize;
     }
 }
 
@@ -163,6 +177,7 @@ fn spawn_text(
     mut text_box: ResMut<TextBox>,
     asset_manager: Res<AssetManager>,
     render_layers: Res<RenderLayerStorage>,
+    data: Res<Data>,
 ) {
     commands.run_system(text_box.refresh_text.unwrap());
     let text_font = TextFont {
@@ -171,20 +186,27 @@ fn spawn_text(
         font_smoothing: bevy::text::FontSmoothing::None,
         ..Default::default()
     };
-    let mut pos = Vec2::new(14.1, -16.);
+    let font_size = text_font.font_size;
+    //glyphs are laid out from the top left of the box
+    let mut pos = Vec2::new(14.1, -16.)
+        + Vec2::new(-bullet_board.width / 2.0, bullet_board.height / 2.0);
     let p = commands
-        .spawn(Transform::from_translation(
-            bullet_board.position.extend(0.0),
+        .spawn((
+            Transform::from_translation(bullet_board.position.extend(0.0)),
+            MenuItem,
         ))
         .with_children(|builder| {
             let e = builder
                 .spawn((
-                    Text2d::new(""),
-                    TextBounds::from(Vec2::new(bullet_board.width, bullet_board.height)),
-                    TextLayout::new(JustifyText::Left, LineBreak::WordBoundary),
+                    GlyphText::new(
+                        text_font,
+                        bullet_board.width,
+                        font_size * data.game.dialogue.glyph_width,
+                        font_size * data.game.dialogue.line_height,
+                    ),
                     Name::new("text"),
-                    text_font,
                     Transform::from_translation((pos).extend(1.0)),
+                    Visibility::Visible,
                     TextBoxText,
                     MenuItem,
                 ))
@@ -0,0 +1,375 @@
+use bevy::prelude::*;
+use rand::{Rng, thread_rng};
+
+use crate::game::{
+    data::data::{TurnData, WeightedEntry},
+    scene::internal::{encounter::Encounter, progress::Progress},
+};
+
+pub struct TurnScriptPlugin;
+impl Plugin for TurnScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TurnPlan>();
+    }
+}
+
+//what the script picked for the current turn
+#[derive(Resource, Default)]
+pub struct TurnPlan {
+    pub attacker: usize,
+    pub attack: String,
+    pub speech: Option<String>,
+
+    //the flavor text is picked once per turn so backing out of a menu keeps it
+    pub text: String,
+    pub text_turn: Option<i32>,
+}
+
+impl TurnPlan {
+    //picks the attack and speech for the enemy's turn that follows the player's action
+    pub fn plan_attack(&mut self, encounter: &Encounter, progress: &Progress) {
+        //the enemies that are still fighting take turns attacking
+        let active = progress.active_enemies();
+        self.attack = "".to_string();
+        self.speech = None;
+        if active.is_empty() {
+            return;
+        }
+        self.attacker = active[progress.turns as usize % active.len()];
+        let enemy = encounter.get_enemy(self.attacker);
+        //an enemy without attacks skips its turn unless a rule gives it one
+        if !enemy.attacks.is_empty() {
+            self.attack = enemy.attacks[progress.turns as usize % enemy.attacks.len()].clone();
+        }
+
+        if let Some(rule) = find_rule(encounter, progress, |r| &r.attacks) {
+            self.attack = pick_weighted(&rule.attacks).unwrap();
+            if let Some(enemy) = rule_enemy(rule, encounter, progress, self.attacker) {
+                self.attacker = enemy;
+            }
+        }
+        if let Some(rule) = find_rule(encounter, progress, |r| &r.speech) {
+            self.speech = pick_weighted(&rule.speech);
+        }
+    }
+    pub fn plan_text(&mut self, encounter: &Encounter, progress: &Progress) {
+        if self.text_turn == Some(progress.turns) {
+            return;
+        }
+        self.text_turn = Some(progress.turns);
+
+        let dialogue_list = &encounter.data.dialogues;
+        self.text = "".to_string();
+        if !dialogue_list.is_empty() {
+            let turn = usize::min(progress.turns as usize, dialogue_list.len() - 1);
+            self.text = dialogue_list[turn].clone();
+        }
+
+        if let Some(rule) = find_rule(encounter, progress, |r| &r.text) {
+            self.text = pick_weighted(&rule.text).unwrap();
+        }
+    }
+}
+
+//enemy a rule is about, killed and spared enemies are left out
+fn rule_enemy(
+    rule: &TurnData,
+    encounter: &Encounter,
+    progress: &Progress,
+    attacker: usize,
+) -> Option<usize> {
+    if rule.enemy.is_empty() {
+        return Some(attacker);
+    }
+    progress
+        .active_enemies()
+        .into_iter()
+        .find(|i| encounter.get_enemy(*i).name == rule.enemy)
+}
+
+pub fn rule_matches(rule: &TurnData, encounter: &Encounter, progress: &Progress) -> bool {
+    let turn = progress.turns;
+    if rule.min_turn.is_some_and(|t| turn < t) || rule.max_turn.is_some_and(|t| turn > t) {
+        return false;
+    }
+
+    if rule.hp_below.is_some() || rule.hp_above.is_some() {
+        let active = progress.active_enemies();
+        if active.is_empty() {
+            return false;
+        }
+        let attacker = active[turn as usize % active.len()];
+        let Some(index) = rule_enemy(rule, encounter, progress, attacker) else {
+            return false;
+        };
+        let health =
+            progress.enemies[index].health as f32 / encounter.get_enemy(index).health as f32;
+        if rule.hp_below.is_some_and(|hp| health >= hp) {
+            return false;
+        }
+        if rule.hp_above.is_some_and(|hp| health <= hp) {
+            return false;
+        }
+    }
+
+    if rule.flags.iter().any(|f| !progress.flags.contains(f)) {
+        return false;
+    }
+    if rule.not_flags.iter().any(|f| progress.flags.contains(f)) {
+        return false;
+    }
+    if rule
+        .items_used
+        .is_some_and(|count| (progress.items_used.len() as i32) < count)
+    {
+        return false;
+    }
+    if !rule.item.is_empty() && !progress.items_used.contains(&rule.item) {
+        return false;
+    }
+    true
+}
+
+//first rule that matches and fills the pool returned by `pool`
+fn find_rule<'a>(
+    encounter: &'a Encounter,
+    progress: &Progress,
+    pool: fn(&TurnData) -> &Vec<WeightedEntry>,
+) -> Option<&'a TurnData> {
+    encounter
+        .data
+        .script
+        .iter()
+        .find(|rule| !pool(rule).is_empty() && rule_matches(rule, encounter, progress))
+}
+
+//entries with a weight of zero or less are never picked,
+//when no entry has a positive weight the first one is used
+pub fn pick_weighted(entries: &[WeightedEntry]) -> Option<String> {
+    if entries.is_empty() {
+        return None;
+    }
+    let total: f32 = entries.iter().map(|e| e.weight.max(0.)).sum();
+    if total <= 0. {
+        return Some(entries[0].name.clone());
+    }
+    let mut rand = thread_rng();
+    let mut roll = rand.gen_range(0.0..total);
+    let mut picked = 0;
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.weight <= 0. {
+            continue;
+        }
+        picked = i;
+        roll -= entry.weight;
+        if roll < 0. {
+            break;
+        }
+    }
+    Some(entries[picked].name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        data::data::{EncounterData, OpponentData},
+        scene::internal::progress::EnemyProgress,
+    };
+
+    fn entry(name: &str, weight: f32) -> WeightedEntry {
+        WeightedEntry {
+            name: name.to_string(),
+            weight,
+        }
+    }
+
+    fn enemy(name: &str, health: i32) -> OpponentData {
+        OpponentData {
+            name: name.to_string(),
+            health,
+            ..Default::default()
+        }
+    }
+
+    fn encounter(script: Vec<TurnData>) -> Encounter {
+        Encounter {
+            data: EncounterData {
+                enemies: vec![enemy("Froggit", 20), enemy("Whimsun", 10)],
+                script,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn progress() -> Progress {
+        Progress {
+            enemies: vec![
+                EnemyProgress {
+                    health: 20,
+                    ..Default::default()
+                },
+                EnemyProgress {
+                    health: 10,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn text_rule(text: &str) -> TurnData {
+        TurnData {
+            text: vec![entry(text, 1.0)],
+            ..Default::default()
+        }
+    }
+
+    fn matches(rule: TurnData, progress: &Progress) -> bool {
+        let encounter = encounter(vec![]);
+        rule_matches(&rule, &encounter, progress)
+    }
+
+    #[test]
+    fn empty_rule_always_matches() {
+        assert!(matches(TurnData::default(), &progress()));
+    }
+
+    #[test]
+    fn turn_range_is_inclusive() {
+        let rule = TurnData {
+            min_turn: Some(2),
+            max_turn: Some(3),
+            ..Default::default()
+        };
+        let mut progress = progress();
+        for (turn, expected) in [(1, false), (2, true), (3, true), (4, false)] {
+            progress.turns = turn;
+            assert_eq!(matches(rule.clone(), &progress), expected, "turn {turn}");
+        }
+    }
+
+    #[test]
+    fn health_conditions_check_the_attacker_by_default() {
+        let below = TurnData {
+            hp_below: Some(0.5),
+            ..Default::default()
+        };
+        let mut progress = progress();
+        assert!(!matches(below.clone(), &progress));
+        progress.enemies[0].health = 9;
+        assert!(matches(below.clone(), &progress));
+        //on turn 1 the second enemy attacks and it is still at full health
+        progress.turns = 1;
+        assert!(!matches(below, &progress));
+
+        let above = TurnData {
+            hp_above: Some(0.5),
+            ..Default::default()
+        };
+        assert!(matches(above.clone(), &progress));
+        progress.enemies[1].health = 5;
+        assert!(!matches(above, &progress));
+    }
+
+    #[test]
+    fn health_conditions_can_name_an_enemy() {
+        let rule = TurnData {
+            enemy: "Whimsun".to_string(),
+            hp_below: Some(0.5),
+            ..Default::default()
+        };
+        let mut progress = progress();
+        progress.enemies[0].health = 1;
+        assert!(!matches(rule.clone(), &progress));
+        progress.enemies[1].health = 1;
+        assert!(matches(rule.clone(), &progress));
+
+        let missing = TurnData {
+            enemy: "Loox".to_string(),
+            ..rule
+        };
+        assert!(!matches(missing, &progress));
+    }
+
+    #[test]
+    fn flags_have_to_be_set_and_not_flags_unset() {
+        let rule = TurnData {
+            flags: vec!["talked".to_string()],
+            not_flags: vec!["insulted".to_string()],
+            ..Default::default()
+        };
+        let mut progress = progress();
+        assert!(!matches(rule.clone(), &progress));
+        progress.flags.insert("talked".to_string());
+        assert!(matches(rule.clone(), &progress));
+        progress.flags.insert("insulted".to_string());
+        assert!(!matches(rule, &progress));
+    }
+
+    #[test]
+    fn item_conditions_check_the_items_used() {
+        let count = TurnData {
+            items_used: Some(2),
+            ..Default::default()
+        };
+        let item = TurnData {
+            item: "Spider Donut".to_string(),
+            ..Default::default()
+        };
+        let mut progress = progress();
+        progress.items_used.push("Monster Candy".to_string());
+        assert!(!matches(count.clone(), &progress));
+        assert!(!matches(item.clone(), &progress));
+        progress.items_used.push("Spider Donut".to_string());
+        assert!(matches(count, &progress));
+        assert!(matches(item, &progress));
+    }
+
+    #[test]
+    fn first_matching_rule_with_a_filled_pool_is_used() {
+        let encounter = encounter(vec![
+            TurnData {
+                min_turn: Some(5),
+                ..text_rule("late")
+            },
+            //no text in this rule, it only picks attacks
+            TurnData {
+                attacks: vec![entry("slam", 1.0)],
+                ..Default::default()
+            },
+            text_rule("first"),
+            text_rule("second"),
+        ]);
+        let mut progress = progress();
+        let rule = find_rule(&encounter, &progress, |r| &r.text).unwrap();
+        assert_eq!(rule.text[0].name, "first");
+        let rule = find_rule(&encounter, &progress, |r| &r.attacks).unwrap();
+        assert_eq!(rule.attacks[0].name, "slam");
+        assert!(find_rule(&encounter, &progress, |r| &r.speech).is_none());
+
+        progress.turns = 5;
+        let rule = find_rule(&encounter, &progress, |r| &r.text).unwrap();
+        assert_eq!(rule.text[0].name, "late");
+    }
+
+    #[test]
+    fn pick_weighted_from_an_empty_pool_is_none() {
+        assert_eq!(pick_weighted(&[]), None);
+    }
+
+    #[test]
+    fn pick_weighted_never_picks_zero_or_negative_weights() {
+        let entries = [entry("never", 0.), entry("always", 1.), entry("nope", -3.)];
+        for _ in 0..100 {
+            assert_eq!(pick_weighted(&entries).as_deref(), Some("always"));
+        }
+    }
+
+    #[test]
+    fn pick_weighted_with_no_positive_weight_picks_the_first() {
+        let entries = [entry("first", 0.), entry("second", 0.), entry("third", -1.)];
+        assert_eq!(pick_weighted(&entries).as_deref(), Some("first"));
+    }
+}
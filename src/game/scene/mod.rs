@@ -1,3 +1,2 @@
-pub mod attacks;
 pub mod battle;
 pub mod internal;
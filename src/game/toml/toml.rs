@@ -7,8 +7,8 @@ use toml;
 pub fn load_contents(filename: String) -> String {
     let contents = match fs::read_to_string(filename.clone()) {
         Ok(c) => c,
-        Err(_) => {
-            log::info!("Could not read file `{}`", filename);
+        Err(e) => {
+            log::error!("Could not read file `{}` : {}", filename, e);
             "".to_string()
         }
     };
@@ -21,8 +21,8 @@ where
 {
     let data: Option<T> = match toml::from_str(&contents) {
         Ok(d) => Some(d),
-        Err(_) => {
-            log::info!("Unable to load data");
+        Err(e) => {
+            log::error!("Unable to load data : {}", e);
             None
         }
     };